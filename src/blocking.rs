@@ -0,0 +1,580 @@
+//! A synchronous client for applications that don't use async rust.
+//!
+//! The [AnimeThemesClient] wraps the async [client](crate::client::AnimeThemesClient)
+//! and runs each request to completion on its own tokio runtime.
+//! Its methods must therefore not be called from within an async runtime.
+//!
+//! Requires the `blocking` feature.
+//!
+//! ```
+//! # use animethemes_rs::error::ApiResult;
+//! use animethemes_rs::blocking::AnimeThemesClient;
+//! use animethemes_rs::fields::Fieldsets;
+//! use animethemes_rs::includes::AnimeInclude;
+//!
+//! # fn a() -> ApiResult<()> {
+//! let client = AnimeThemesClient::default();
+//! let anime = client.anime(
+//!     "bakemonogatari",
+//!     AnimeInclude::default().themes(),
+//!     Fieldsets::default(),
+//! )?;
+//! println!("{:?} has {} themes", anime.name, anime.themes.unwrap().len());
+//! # Ok(()) }
+//! ```
+
+use crate::cache::CacheStats;
+use crate::client::AnimeThemesClientBuilder;
+use crate::dump::DownloadProgress;
+use crate::error::ApiResult;
+use crate::fields::Fieldsets;
+use crate::filters::*;
+use crate::includes::*;
+use crate::models::{
+    Anime, AnimeSynonym, AnimeYear, Announcement, Artist, Audio, Dump, FeaturedTheme, Image,
+    NewPlaylist, NewPlaylistTrack, Page, Playlist, PlaylistTrack, PlaylistTrackUpdate,
+    PlaylistUpdate, Resource, SearchResponse, Series, Song, Studio, Theme, ThemeEntry, User, Video,
+    VideoScript,
+};
+use crate::pagination::{PaginateOptions, Pagination};
+use crate::rate_limit::RateLimiter;
+use crate::sort::*;
+use futures::stream::LocalBoxStream;
+use futures::{Future, StreamExt};
+use serde::de::DeserializeOwned;
+use std::io::Write;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+use tokio::runtime::Runtime;
+
+/// Generates methods that block on the method of the same name of the async client
+macro_rules! blocking {
+    ($($(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// A client with the same methods as the async client that block until the response is received.
+/// Clones share the runtime, cache and rate limiter.
+#[derive(Clone, Debug)]
+pub struct AnimeThemesClient {
+    inner: crate::client::AnimeThemesClient,
+    runtime: Arc<Runtime>,
+}
+
+impl Default for AnimeThemesClient {
+    fn default() -> Self {
+        Self::with_client(crate::client::AnimeThemesClient::default())
+            .expect("failed to build the default client")
+    }
+}
+
+impl AnimeThemesClient {
+    /// Creates a new blocking client
+    /// Use [AnimeThemesClient::builder] and [AnimeThemesClient::with_client] for further configuration
+    pub fn new(api_endpoint: &str, video_endpoint: &str) -> Self {
+        Self::with_client(crate::client::AnimeThemesClient::new(
+            api_endpoint,
+            video_endpoint,
+        ))
+        .expect("failed to build the client")
+    }
+
+    /// Returns a builder to configure the async client wrapped by [AnimeThemesClient::with_client]
+    pub fn builder() -> AnimeThemesClientBuilder {
+        AnimeThemesClientBuilder::default()
+    }
+
+    /// Wraps a configured async client
+    ///
+    /// ```
+    /// # use animethemes_rs::error::ApiResult;
+    /// use animethemes_rs::blocking::AnimeThemesClient;
+    /// use animethemes_rs::cache::ResponseCache;
+    ///
+    /// # fn a() -> ApiResult<()> {
+    /// let client = AnimeThemesClient::with_client(
+    ///     AnimeThemesClient::builder()
+    ///         .cache(ResponseCache::memory(100))
+    ///         .build()?,
+    /// )?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_client(client: crate::client::AnimeThemesClient) -> ApiResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Returns the wrapped async client
+    pub fn client(&self) -> &crate::client::AnimeThemesClient {
+        &self.inner
+    }
+
+    /// Returns the rate limiter used by the client if rate limiting is enabled
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.inner.rate_limiter()
+    }
+
+    /// Returns the hit and miss counters of the response cache if one is configured
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+
+    blocking! {
+        /// Searches for all types provided by the api
+        /// See [crate::client::AnimeThemesClient::search]
+        fn search(
+            &self,
+            query: &str,
+            fields: Fieldsets,
+            include: SearchIncludes,
+            filter: SearchFilters,
+            sort: SearchSorts
+        ) -> ApiResult<SearchResponse>;
+
+        /// Returns an anime by a given slug string
+        fn anime(&self, slug: &str, include: AnimeInclude, fields: Fieldsets) -> ApiResult<Anime>;
+
+        /// Returns an artist by a given slug string
+        fn artist(&self, slug: &str, include: ArtistInclude, fields: Fieldsets) -> ApiResult<Artist>;
+
+        /// Returns an audio by basename
+        fn audio(&self, basename: &str, include: AudioInclude, fields: Fieldsets) -> ApiResult<Audio>;
+
+        /// Returns an entry by a given id
+        fn entry(&self, id: u32, include: ThemeEntryInclude, fields: Fieldsets) -> ApiResult<ThemeEntry>;
+
+        /// Returns an image by id
+        fn image(&self, id: u32, include: ImageInclude, fields: Fieldsets) -> ApiResult<Image>;
+
+        /// Returns a resource by id
+        fn resource(&self, id: u32, include: ResourceInclude, fields: Fieldsets) -> ApiResult<Resource>;
+
+        /// Returns a series by slug
+        fn series(&self, slug: &str, include: SeriesInclude, fields: Fieldsets) -> ApiResult<Series>;
+
+        /// Returns a song by id
+        fn song(&self, id: u32, include: SongInclude, fields: Fieldsets) -> ApiResult<Song>;
+
+        /// Returns a studio by slug
+        fn studio(&self, slug: &str, include: StudioInclude, fields: Fieldsets) -> ApiResult<Studio>;
+
+        /// Returns a synonym by id
+        fn synonym(&self, id: u32, include: SynonymInclude, fields: Fieldsets) -> ApiResult<AnimeSynonym>;
+
+        /// Returns a theme by id
+        fn theme(&self, id: u32, include: ThemeInclude, fields: Fieldsets) -> ApiResult<Theme>;
+
+        /// Returns a video by basename
+        fn video(&self, basename: &str, include: VideoInclude, fields: Fieldsets) -> ApiResult<Video>;
+
+        /// Returns a video script by id
+        fn video_script(
+            &self,
+            id: u32,
+            include: VideoScriptInclude,
+            fields: Fieldsets
+        ) -> ApiResult<VideoScript>;
+
+        /// Returns a page of anime
+        fn list_anime(
+            &self,
+            pagination: Pagination,
+            include: AnimeInclude,
+            filter: AnimeFilter,
+            sort: Sort<AnimeSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Anime>>;
+
+        /// Returns a page of artists
+        fn list_artists(
+            &self,
+            pagination: Pagination,
+            include: ArtistInclude,
+            filter: ArtistFilter,
+            sort: Sort<ArtistSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Artist>>;
+
+        /// Returns a page of audios
+        fn list_audio(
+            &self,
+            pagination: Pagination,
+            include: AudioInclude,
+            filter: AudioFilter,
+            sort: Sort<AudioSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Audio>>;
+
+        /// Returns a page of theme entries
+        fn list_entries(
+            &self,
+            pagination: Pagination,
+            include: ThemeEntryInclude,
+            filter: ThemeEntryFilter,
+            sort: Sort<ThemeEntrySort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<ThemeEntry>>;
+
+        /// Returns a page of images
+        fn list_images(
+            &self,
+            pagination: Pagination,
+            include: ImageInclude,
+            filter: ImageFilter,
+            sort: Sort<ImageSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Image>>;
+
+        /// Returns a page of resources
+        fn list_resources(
+            &self,
+            pagination: Pagination,
+            include: ResourceInclude,
+            filter: ResourceFilter,
+            sort: Sort<ResourceSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Resource>>;
+
+        /// Returns a page of series
+        fn list_series(
+            &self,
+            pagination: Pagination,
+            include: SeriesInclude,
+            filter: SeriesFilter,
+            sort: Sort<SeriesSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Series>>;
+
+        /// Returns a page of songs
+        fn list_songs(
+            &self,
+            pagination: Pagination,
+            include: SongInclude,
+            filter: SongFilter,
+            sort: Sort<SongSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Song>>;
+
+        /// Returns a page of studios
+        fn list_studios(
+            &self,
+            pagination: Pagination,
+            include: StudioInclude,
+            filter: StudioFilter,
+            sort: Sort<StudioSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Studio>>;
+
+        /// Returns a page of synonyms
+        fn list_synonyms(
+            &self,
+            pagination: Pagination,
+            include: SynonymInclude,
+            filter: SynonymFilter,
+            sort: Sort<SynonymSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<AnimeSynonym>>;
+
+        /// Returns a page of themes
+        fn list_themes(
+            &self,
+            pagination: Pagination,
+            include: ThemeInclude,
+            filter: ThemeFilter,
+            sort: Sort<ThemeSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Theme>>;
+
+        /// Returns a page of videos
+        fn list_videos(
+            &self,
+            pagination: Pagination,
+            include: VideoInclude,
+            filter: VideoFilter,
+            sort: Sort<VideoSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Video>>;
+
+        /// Returns a page of video scripts
+        fn list_video_scripts(
+            &self,
+            pagination: Pagination,
+            include: VideoScriptInclude,
+            filter: VideoScriptFilter,
+            sort: Sort<VideoScriptSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<VideoScript>>;
+
+        /// Downloads the content of a video script from its link
+        fn download_video_script(&self, script: &VideoScript) -> ApiResult<String>;
+
+        /// Returns the user the configured token belongs to
+        fn me(&self, include: UserInclude, fields: Fieldsets) -> ApiResult<User>;
+
+        /// Returns a page of the playlists of the user the configured token belongs to,
+        /// including private and unlisted playlists
+        fn my_playlists(
+            &self,
+            pagination: Pagination,
+            include: PlaylistInclude,
+            filter: PlaylistFilter,
+            sort: Sort<PlaylistSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Playlist>>;
+
+        /// Returns a playlist by its hashid
+        fn playlist(&self, id: &str, include: PlaylistInclude, fields: Fieldsets) -> ApiResult<Playlist>;
+
+        /// Returns a page of public playlists
+        fn list_playlists(
+            &self,
+            pagination: Pagination,
+            include: PlaylistInclude,
+            filter: PlaylistFilter,
+            sort: Sort<PlaylistSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Playlist>>;
+
+        /// Returns a track of a playlist by the hashids of the playlist and track
+        fn playlist_track(
+            &self,
+            playlist_id: &str,
+            track_id: &str,
+            include: PlaylistTrackInclude,
+            fields: Fieldsets
+        ) -> ApiResult<PlaylistTrack>;
+
+        /// Returns a page of the tracks of a playlist
+        fn list_playlist_tracks(
+            &self,
+            playlist_id: &str,
+            pagination: Pagination,
+            include: PlaylistTrackInclude,
+            filter: PlaylistTrackFilter,
+            sort: Sort<PlaylistTrackSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<PlaylistTrack>>;
+
+        /// Returns all tracks of a playlist in playback order
+        /// by following the `previous` and `next` links of the tracks
+        fn playlist_tracks_in_order(
+            &self,
+            playlist_id: &str,
+            include: PlaylistTrackInclude,
+            fields: Fieldsets
+        ) -> ApiResult<Vec<PlaylistTrack>>;
+
+        /// Creates a new playlist owned by the authenticated user
+        fn create_playlist(&self, playlist: &NewPlaylist) -> ApiResult<Playlist>;
+
+        /// Updates a playlist of the authenticated user
+        fn update_playlist(&self, id: &str, update: &PlaylistUpdate) -> ApiResult<Playlist>;
+
+        /// Deletes a playlist of the authenticated user
+        fn delete_playlist(&self, id: &str) -> ApiResult<()>;
+
+        /// Adds a track to a playlist of the authenticated user
+        fn create_playlist_track(
+            &self,
+            playlist_id: &str,
+            track: &NewPlaylistTrack
+        ) -> ApiResult<PlaylistTrack>;
+
+        /// Updates a track of a playlist of the authenticated user
+        fn update_playlist_track(
+            &self,
+            playlist_id: &str,
+            track_id: &str,
+            update: &PlaylistTrackUpdate
+        ) -> ApiResult<PlaylistTrack>;
+
+        /// Removes a track from a playlist of the authenticated user
+        fn delete_playlist_track(&self, playlist_id: &str, track_id: &str) -> ApiResult<()>;
+
+        /// Returns an announcement by id
+        fn announcement(&self, id: u32, fields: Fieldsets) -> ApiResult<Announcement>;
+
+        /// Returns a page of announcements
+        fn list_announcements(
+            &self,
+            pagination: Pagination,
+            filter: AnnouncementFilter,
+            sort: Sort<AnnouncementSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Announcement>>;
+
+        /// Returns a database dump by id
+        fn dump(&self, id: u32, fields: Fieldsets) -> ApiResult<Dump>;
+
+        /// Returns a page of database dumps
+        fn list_dumps(
+            &self,
+            pagination: Pagination,
+            filter: DumpFilter,
+            sort: Sort<DumpSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<Dump>>;
+
+        /// Returns the newest database dump matching the filter
+        fn latest_dump(&self, filter: DumpFilter) -> ApiResult<Option<Dump>>;
+
+        /// Returns a featured theme by id
+        fn featured_theme(
+            &self,
+            id: u32,
+            include: FeaturedThemeInclude,
+            fields: Fieldsets
+        ) -> ApiResult<FeaturedTheme>;
+
+        /// Returns a page of featured themes
+        fn list_featured_themes(
+            &self,
+            pagination: Pagination,
+            include: FeaturedThemeInclude,
+            filter: FeaturedThemeFilter,
+            sort: Sort<FeaturedThemeSort>,
+            fields: Fieldsets
+        ) -> ApiResult<Page<FeaturedTheme>>;
+
+        /// Returns the theme that is currently featured on the homepage
+        fn current_featured_theme(
+            &self,
+            include: FeaturedThemeInclude,
+            fields: Fieldsets
+        ) -> ApiResult<FeaturedTheme>;
+
+        /// Returns all years that have anime
+        fn anime_years(&self) -> ApiResult<Vec<u16>>;
+
+        /// Returns the anime of a year grouped by season
+        fn anime_year(&self, year: u16, include: AnimeInclude, fields: Fieldsets) -> ApiResult<AnimeYear>;
+    }
+
+    /// Downloads a database dump into the writer and reports the progress after each chunk.
+    /// Returns the number of bytes written.
+    pub fn download_dump<W, F>(&self, dump: &Dump, writer: &mut W, progress: F) -> ApiResult<u64>
+    where
+        W: Write,
+        F: FnMut(DownloadProgress),
+    {
+        let mut writer = BlockingWriter(writer);
+        self.runtime
+            .block_on(self.inner.download_dump(dump, &mut writer, progress))
+    }
+
+    /// Downloads the newest database dump matching the filter into the writer.
+    /// Returns the downloaded dump or None if there's no dump matching the filter.
+    pub fn download_latest_dump<W, F>(
+        &self,
+        filter: DumpFilter,
+        writer: &mut W,
+        progress: F,
+    ) -> ApiResult<Option<Dump>>
+    where
+        W: Write,
+        F: FnMut(DownloadProgress),
+    {
+        let mut writer = BlockingWriter(writer);
+        self.runtime.block_on(
+            self.inner
+                .download_latest_dump(filter, &mut writer, progress),
+        )
+    }
+
+    /// Walks through all pages of an index endpoint and yields the items of each page.
+    /// The pages are fetched with the async client passed to `fetch` while iterating.
+    /// See [crate::client::AnimeThemesClient::paginate]
+    ///
+    /// ```
+    /// # use animethemes_rs::error::ApiResult;
+    /// use animethemes_rs::blocking::AnimeThemesClient;
+    /// use animethemes_rs::fields::Fieldsets;
+    /// use animethemes_rs::filters::VideoFilter;
+    /// use animethemes_rs::includes::VideoInclude;
+    /// use animethemes_rs::pagination::PaginateOptions;
+    /// use animethemes_rs::sort::Sort;
+    ///
+    /// # fn a() -> ApiResult<()> {
+    /// let client = AnimeThemesClient::default();
+    /// let options = PaginateOptions::default().page_size(100);
+    /// let videos = client.paginate(options, |client, page| {
+    ///     client.list_videos(
+    ///         page,
+    ///         VideoInclude::default(),
+    ///         VideoFilter::default(),
+    ///         Sort::default(),
+    ///         Fieldsets::default(),
+    ///     )
+    /// });
+    ///
+    /// for video in videos {
+    ///     println!("{:?}", video?.basename);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn paginate<'a, T, F, Fut>(
+        &'a self,
+        options: PaginateOptions,
+        fetch: F,
+    ) -> impl Iterator<Item = ApiResult<T>> + 'a
+    where
+        T: DeserializeOwned + Send + 'a,
+        F: FnOnce(&'a crate::client::AnimeThemesClient, Pagination) -> Fut + 'a,
+        Fut: Future<Output = ApiResult<Page<T>>> + 'a,
+    {
+        Pages {
+            runtime: &self.runtime,
+            stream: self.inner.paginate(options, fetch).boxed_local(),
+        }
+    }
+}
+
+/// Iterates over a stream by blocking on each item
+struct Pages<'a, T> {
+    runtime: &'a Runtime,
+    stream: LocalBoxStream<'a, T>,
+}
+
+impl<T> Iterator for Pages<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+/// Passes the writes of the async client to a blocking writer.
+/// Blocking is fine here as the runtime only runs the request of the blocking client.
+struct BlockingWriter<'a, W>(&'a mut W);
+
+impl<W: Write> AsyncWrite for BlockingWriter<'_, W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
@@ -0,0 +1,425 @@
+//! Caching of api responses.
+//!
+//! Responses are cached by the path and query of the request and served from the cache
+//! until their ttl expires. Responses of authenticated requests are cached separately
+//! per token, and writes to playlists evict the cached playlist responses. Expired responses are revalidated with the `ETag` and
+//! `Last-Modified` headers of the cached response so unchanged content isn't downloaded again.
+//!
+//! ```
+//! # use animethemes_rs::error::ApiResult;
+//! use animethemes_rs::cache::ResponseCache;
+//! use animethemes_rs::client::AnimeThemesClient;
+//! use std::time::Duration;
+//!
+//! # fn a() -> ApiResult<()> {
+//! let client = AnimeThemesClient::builder()
+//!     .cache(
+//!         ResponseCache::tiered(1000, "/tmp/animethemes-cache")
+//!             .ttl(Duration::from_secs(600))
+//!             .endpoint_ttl("/animeyear", Duration::from_secs(60 * 60 * 24)),
+//!     )
+//!     .build()?;
+//! # Ok(()) }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub static DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// A storage for cached responses
+pub trait CacheBackend: Debug + Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, key: &str, response: CachedResponse);
+    /// Removes all responses with keys starting with the prefix
+    fn remove_prefix(&self, prefix: &str);
+}
+
+/// A response body with the headers needed for revalidation
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// The time the response was stored or last revalidated in seconds since the unix epoch
+    pub stored_at: u64,
+}
+
+impl CachedResponse {
+    pub fn new(body: String, etag: Option<String>, last_modified: Option<String>) -> Self {
+        Self {
+            body,
+            etag,
+            last_modified,
+            stored_at: now(),
+        }
+    }
+
+    /// Returns how long ago the response was stored or revalidated
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.stored_at))
+    }
+
+    /// Marks the response as fresh after it has been revalidated
+    pub fn refresh(&mut self) {
+        self.stored_at = now();
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// An in-memory cache that evicts the least recently used responses
+/// when the capacity is reached
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    tick: u64,
+    entries: HashMap<String, (u64, CachedResponse)>,
+    usage: BTreeMap<u64, String>,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) -> Option<&CachedResponse> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (last_used, response) = self.entries.get_mut(key)?;
+        self.usage.remove(last_used);
+        self.usage.insert(tick, key.to_string());
+        *last_used = tick;
+
+        Some(response)
+    }
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CacheBackend for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.state.lock().unwrap().touch(key).cloned()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+
+        if let Some((last_used, _)) = state.entries.remove(key) {
+            state.usage.remove(&last_used);
+        }
+        while state.entries.len() >= self.capacity {
+            let Some((_, oldest)) = state.usage.pop_first() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        state.usage.insert(tick, key.to_string());
+        state.entries.insert(key.to_string(), (tick, response));
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        let mut state = self.state.lock().unwrap();
+        let LruState { entries, usage, .. } = &mut *state;
+
+        entries.retain(|key, (last_used, _)| {
+            let keep = !key.starts_with(prefix);
+
+            if !keep {
+                usage.remove(last_used);
+            }
+            keep
+        });
+    }
+}
+
+/// A cache that stores each response as a json file in a directory.
+/// Failing to read or write a file is logged and treated like a missing entry.
+#[derive(Debug)]
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    response: CachedResponse,
+}
+
+impl DiskCache {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.json", stable_hash(key)))
+    }
+}
+
+impl CacheBackend for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let content = std::fs::read(self.path(key)).ok()?;
+
+        match serde_json::from_slice::<DiskEntry>(&content) {
+            Ok(entry) if entry.key == key => Some(entry.response),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("invalid cache entry for {key}: {e}");
+                None
+            }
+        }
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            response,
+        };
+        let result = std::fs::create_dir_all(&self.directory)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_vec(&entry).map_err(|e| e.to_string()))
+            .and_then(|content| std::fs::write(self.path(key), content).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            tracing::warn!("failed to write cache entry for {key}: {e}");
+        }
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        // the file names are hashes, so the keys have to be read from the entries
+        let Ok(files) = std::fs::read_dir(&self.directory) else {
+            return;
+        };
+
+        for path in files.flatten().map(|file| file.path()) {
+            let matches = std::fs::read(&path)
+                .ok()
+                .and_then(|content| serde_json::from_slice::<DiskEntry>(&content).ok())
+                .is_some_and(|entry| entry.key.starts_with(prefix));
+
+            if matches {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    tracing::warn!("failed to remove cache entry {}: {e}", path.display());
+                }
+            }
+        }
+    }
+}
+
+/// Hashes a key with FNV-1a, which keeps file names stable between builds
+pub(crate) fn stable_hash(key: &str) -> u64 {
+    stable_hash_bytes(key.as_bytes())
+}
+
+pub(crate) fn stable_hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// An in-memory cache in front of a disk cache.
+/// Responses found on disk are moved into memory when they're requested.
+#[derive(Debug)]
+pub struct TieredCache {
+    memory: MemoryCache,
+    disk: DiskCache,
+}
+
+impl TieredCache {
+    pub fn new(memory: MemoryCache, disk: DiskCache) -> Self {
+        Self { memory, disk }
+    }
+}
+
+impl CacheBackend for TieredCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.memory.get(key).or_else(|| {
+            let response = self.disk.get(key)?;
+            self.memory.put(key, response.clone());
+
+            Some(response)
+        })
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        self.memory.put(key, response.clone());
+        self.disk.put(key, response);
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        self.memory.remove_prefix(prefix);
+        self.disk.remove_prefix(prefix);
+    }
+}
+
+/// The number of requests served by the cache
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Requests served from the cache without contacting the api
+    pub hits: u64,
+    /// Requests that had to download the response
+    pub misses: u64,
+    /// Requests served from the cache after the api confirmed that the response didn't change
+    pub revalidations: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    revalidations: AtomicU64,
+}
+
+/// The response cache used by the client. Clones share the same storage and counters.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    backend: Arc<dyn CacheBackend>,
+    ttl: Duration,
+    endpoint_ttls: Vec<(String, Duration)>,
+    counters: Arc<Counters>,
+}
+
+impl ResponseCache {
+    /// Creates a cache with a custom backend and the [DEFAULT_CACHE_TTL]
+    pub fn new<B: CacheBackend + 'static>(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            ttl: DEFAULT_CACHE_TTL,
+            endpoint_ttls: Vec::new(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Creates an in-memory cache holding up to `capacity` responses
+    pub fn memory(capacity: usize) -> Self {
+        Self::new(MemoryCache::new(capacity))
+    }
+
+    /// Creates a cache storing the responses in the given directory
+    pub fn disk<P: Into<PathBuf>>(directory: P) -> Self {
+        Self::new(DiskCache::new(directory))
+    }
+
+    /// Creates an in-memory cache holding up to `capacity` responses
+    /// backed by a cache in the given directory
+    pub fn tiered<P: Into<PathBuf>>(capacity: usize, directory: P) -> Self {
+        Self::new(TieredCache::new(
+            MemoryCache::new(capacity),
+            DiskCache::new(directory),
+        ))
+    }
+
+    /// Sets how long responses are served without revalidation
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+
+        self
+    }
+
+    /// Sets the ttl for a path like `/anime` and all paths below it like `/anime/bakemonogatari`.
+    /// Other paths sharing the prefix like `/animeyear` aren't affected.
+    /// The longest matching prefix is used.
+    pub fn endpoint_ttl<S: ToString>(mut self, prefix: S, ttl: Duration) -> Self {
+        self.endpoint_ttls.push((prefix.to_string(), ttl));
+
+        self
+    }
+
+    /// Returns the number of hits, misses and revalidations so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            revalidations: self.counters.revalidations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the cache key for a request to the url of an endpoint. The query parameters
+    /// are sorted so their order doesn't matter. Authenticated requests are keyed by a hash
+    /// of the token so users never see each other's responses.
+    pub(crate) fn key(url: &str, query: &[(String, String)], token: Option<&str>) -> String {
+        let mut query: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        query.sort();
+        let key = format!("{url}?{}", query.join("&"));
+
+        match token {
+            Some(token) => format!("{key}#{:016x}", stable_hash(token)),
+            None => key,
+        }
+    }
+
+    pub(crate) fn ttl_for(&self, path: &str) -> Duration {
+        self.endpoint_ttls
+            .iter()
+            .filter(|(prefix, _)| is_path_prefix(prefix, path))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, ttl)| *ttl)
+            .unwrap_or(self.ttl)
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.backend.get(key)
+    }
+
+    pub(crate) fn put(&self, key: &str, response: CachedResponse) {
+        self.backend.put(key, response)
+    }
+
+    /// Removes the responses of the url and all urls below it
+    pub(crate) fn invalidate(&self, url: &str) {
+        self.backend.remove_prefix(&format!("{url}?"));
+        self.backend.remove_prefix(&format!("{url}/"));
+    }
+
+    pub(crate) fn record_hit(&self) {
+        self.counters.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_miss(&self) {
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_revalidation(&self) {
+        self.counters.revalidations.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns whether the path is the prefix itself or below it
+fn is_path_prefix(prefix: &str, path: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+}
@@ -0,0 +1,59 @@
+use crate::models::{
+    Anime, AnimeSynonym, Artist, EntryMetadata, Series, Song, Theme, ThemeEntry, Video,
+};
+
+/// Fills the attributes missing from a sparse copy of an entry with those of the stored entry
+pub(super) trait Merge {
+    fn merge(&mut self, stored: Self);
+}
+
+/// An attribute that is missing from a sparse copy if it's None.
+/// Attributes that are present are fresher than the stored ones and take precedence.
+trait Attribute {
+    fn fill(&mut self, stored: Self);
+}
+
+impl<T> Attribute for Option<T> {
+    fn fill(&mut self, stored: Self) {
+        if self.is_none() {
+            *self = stored;
+        }
+    }
+}
+
+impl Merge for EntryMetadata {
+    fn merge(&mut self, stored: Self) {
+        self.created_at.fill(stored.created_at);
+        self.updated_at.fill(stored.updated_at);
+        self.deleted_at.fill(stored.deleted_at);
+    }
+}
+
+macro_rules! merge {
+    ($($type: ty { $($field: ident),* })*) => {
+        $(
+            impl Merge for $type {
+                fn merge(&mut self, stored: Self) {
+                    self.meta.merge(stored.meta);
+                    $(self.$field.fill(stored.$field);)*
+                }
+            }
+        )*
+    };
+}
+
+// relations that are stored as edges of the catalogue are taken from the entries before
+// inserting them, the remaining ones stay nested
+merge! {
+    Anime { name, slug, year, season, synopsis, resource, images, studios }
+    AnimeSynonym { text }
+    Series { name, slug }
+    Theme { theme_type, sequence, group, slug }
+    ThemeEntry { version, episodes, nsfw, spoiler, notes }
+    Video {
+        basename, filename, path, resolution, no_credits, subbed, lyrics, uncensored,
+        source, overlap, link, tags, audio, script
+    }
+    Song { title }
+    Artist { name, slug, groups, members }
+}
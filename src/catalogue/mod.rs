@@ -0,0 +1,527 @@
+//! An offline store for entries fetched from the api or imported from a database dump.
+//!
+//! The api returns related entries as nested copies, so the same anime is contained in
+//! every theme that references it. The [Catalogue] stores each entry once by its id
+//! and keeps the relations between them separately so they can be navigated in both directions.
+//!
+//! ```
+//! use animethemes_rs::catalogue::Catalogue;
+//! use animethemes_rs::dump::DumpData;
+//! # use animethemes_rs::error::ApiResult;
+//!
+//! # fn a(data: DumpData) -> ApiResult<()> {
+//! let catalogue = Catalogue::from(data);
+//!
+//! for anime in catalogue.anime_of_video("Bakemonogatari-OP1.webm") {
+//!     let themes = anime.meta.id.map_or(0, |id| catalogue.themes_of(id).len());
+//!     println!("{:?} has {themes} themes", anime.name);
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::dump::DumpData;
+use crate::models::{
+    Anime, AnimeSynonym, Artist, EntryMetadata, SearchResponse, Series, Song, Theme, ThemeEntry,
+    Video,
+};
+use merge::Merge;
+use search::{index, SearchIndex};
+use std::collections::{BTreeSet, HashMap};
+
+mod merge;
+pub(crate) mod normalize;
+mod search;
+
+/// A relation between two types of entries that can be followed in both directions
+#[derive(Clone, Debug, Default)]
+struct Edges {
+    forward: HashMap<u32, BTreeSet<u32>>,
+    backward: HashMap<u32, BTreeSet<u32>>,
+}
+
+impl Edges {
+    /// Links two entries. Entries without an id are ignored.
+    fn link<F: Into<Option<u32>>, T: Into<Option<u32>>>(&mut self, from: F, to: T) {
+        let (Some(from), Some(to)) = (from.into(), to.into()) else {
+            return;
+        };
+        self.forward.entry(from).or_default().insert(to);
+        self.backward.entry(to).or_default().insert(from);
+    }
+
+    fn forward(&self, from: u32) -> impl Iterator<Item = u32> + '_ {
+        self.forward.get(&from).into_iter().flatten().copied()
+    }
+
+    fn backward(&self, to: u32) -> impl Iterator<Item = u32> + '_ {
+        self.backward.get(&to).into_iter().flatten().copied()
+    }
+}
+
+/// Entries stored by id per type with the relations between them.
+/// Inserting an entry that already exists updates the attributes it contains
+/// while the missing attributes and the known relations are kept.
+/// Entries without an id can't be identified and are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct Catalogue {
+    anime: HashMap<u32, Anime>,
+    synonyms: HashMap<u32, AnimeSynonym>,
+    series: HashMap<u32, Series>,
+    themes: HashMap<u32, Theme>,
+    entries: HashMap<u32, ThemeEntry>,
+    videos: HashMap<u32, Video>,
+    songs: HashMap<u32, Song>,
+    artists: HashMap<u32, Artist>,
+    video_basenames: HashMap<String, u32>,
+    /// the character an artist performs a song as, keyed by (song_id, artist_id)
+    song_credits: HashMap<(u32, u32), String>,
+    anime_synonyms: Edges,
+    anime_series: Edges,
+    anime_themes: Edges,
+    song_themes: Edges,
+    theme_entries: Edges,
+    entry_videos: Edges,
+    song_artists: Edges,
+    search_index: SearchIndex,
+}
+
+macro_rules! lookup {
+    ($map: ident, $ids: expr) => {
+        $ids.filter_map(|id| $map.get(&id)).collect()
+    };
+}
+
+impl Catalogue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an anime and all nested relations
+    pub fn insert_anime(&mut self, mut anime: Anime) {
+        let Some(id) = identify("anime", &anime.meta) else {
+            return;
+        };
+
+        for synonym in anime.synonyms.take().into_iter().flatten() {
+            self.anime_synonyms.link(id, synonym.meta.id);
+            self.insert_synonym(synonym);
+        }
+        for series in anime.series.take().into_iter().flatten() {
+            self.anime_series.link(id, series.meta.id);
+            self.insert_series(series);
+        }
+        for theme in anime.themes.take().into_iter().flatten() {
+            self.anime_themes.link(id, theme.meta.id);
+            self.insert_theme(theme);
+        }
+        let anime = store(&mut self.anime, id, anime);
+        index(&mut self.search_index.anime, id, anime);
+    }
+
+    /// Inserts an anime synonym and all nested relations
+    pub fn insert_synonym(&mut self, mut synonym: AnimeSynonym) {
+        let Some(id) = identify("synonym", &synonym.meta) else {
+            return;
+        };
+
+        if let Some(anime) = synonym.anime.take() {
+            self.anime_synonyms.link(anime.meta.id, id);
+            self.insert_anime(anime);
+        }
+        let synonym = store(&mut self.synonyms, id, synonym);
+        index(&mut self.search_index.synonyms, id, synonym);
+    }
+
+    /// Inserts a series and all nested relations
+    pub fn insert_series(&mut self, mut series: Series) {
+        let Some(id) = identify("series", &series.meta) else {
+            return;
+        };
+
+        for anime in series.anime.take().into_iter().flatten() {
+            self.anime_series.link(anime.meta.id, id);
+            self.insert_anime(anime);
+        }
+        let series = store(&mut self.series, id, series);
+        index(&mut self.search_index.series, id, series);
+    }
+
+    /// Inserts a theme and all nested relations
+    pub fn insert_theme(&mut self, mut theme: Theme) {
+        let Some(id) = identify("theme", &theme.meta) else {
+            return;
+        };
+
+        if let Some(anime) = theme.anime.take() {
+            self.anime_themes.link(anime.meta.id, id);
+            self.insert_anime(anime);
+        }
+        if let Some(song) = theme.song.take() {
+            self.song_themes.link(song.meta.id, id);
+            self.insert_song(song);
+        }
+        for entry in theme.entries.take().into_iter().flatten() {
+            self.theme_entries.link(id, entry.meta.id);
+            self.insert_entry(entry);
+        }
+        let theme = store(&mut self.themes, id, theme);
+        index(&mut self.search_index.themes, id, theme);
+    }
+
+    /// Inserts a theme entry and all nested relations
+    pub fn insert_entry(&mut self, mut entry: ThemeEntry) {
+        let Some(id) = identify("entry", &entry.meta) else {
+            return;
+        };
+
+        if let Some(theme) = entry.theme.take() {
+            self.theme_entries.link(theme.meta.id, id);
+            self.insert_theme(theme);
+        }
+        for video in entry.videos.take().into_iter().flatten() {
+            self.entry_videos.link(id, video.meta.id);
+            self.insert_video(video);
+        }
+        store(&mut self.entries, id, entry);
+    }
+
+    /// Inserts a video and all nested relations
+    pub fn insert_video(&mut self, mut video: Video) {
+        let Some(id) = identify("video", &video.meta) else {
+            return;
+        };
+
+        for entry in video.entries.take().into_iter().flatten() {
+            self.entry_videos.link(entry.meta.id, id);
+            self.insert_entry(entry);
+        }
+        let video = store(&mut self.videos, id, video);
+        index(&mut self.search_index.videos, id, video);
+
+        if let Some(basename) = &video.basename {
+            self.video_basenames.insert(basename.clone(), id);
+        }
+    }
+
+    /// Inserts a song and all nested relations
+    pub fn insert_song(&mut self, mut song: Song) {
+        let Some(id) = identify("song", &song.meta) else {
+            return;
+        };
+
+        for theme in song.themes.take().into_iter().flatten() {
+            self.song_themes.link(id, theme.meta.id);
+            self.insert_theme(theme);
+        }
+        for artist in song.artists.take().into_iter().flatten() {
+            self.link_song_artist(id, &artist);
+            self.insert_artist(artist);
+        }
+        let song = store(&mut self.songs, id, song);
+        index(&mut self.search_index.songs, id, song);
+    }
+
+    /// Inserts an artist and all nested relations.
+    /// Groups and members stay nested in the artist.
+    pub fn insert_artist(&mut self, mut artist: Artist) {
+        let Some(id) = identify("artist", &artist.meta) else {
+            return;
+        };
+
+        for song in artist.songs.take().into_iter().flatten() {
+            self.song_artists.link(song.meta.id, id);
+            self.insert_song(song);
+        }
+        // the character and membership are attributes of the relation to a song or group.
+        // The character is stored separately when the artist is inserted as part of a song
+        artist.as_character = None;
+        artist.membership = None;
+        let artist = store(&mut self.artists, id, artist);
+        index(&mut self.search_index.artists, id, artist);
+    }
+
+    fn link_song_artist(&mut self, song_id: u32, artist: &Artist) {
+        self.song_artists.link(song_id, artist.meta.id);
+
+        if let (Some(artist_id), Some(character)) = (artist.meta.id, &artist.as_character) {
+            self.song_credits
+                .insert((song_id, artist_id), character.clone());
+        }
+    }
+
+    /// Inserts all results of a search
+    pub fn insert_search(&mut self, response: SearchResponse) {
+        response
+            .anime
+            .into_iter()
+            .flatten()
+            .for_each(|a| self.insert_anime(a));
+        response
+            .themes
+            .into_iter()
+            .flatten()
+            .for_each(|t| self.insert_theme(t));
+        response
+            .artists
+            .into_iter()
+            .flatten()
+            .for_each(|a| self.insert_artist(a));
+        response
+            .series
+            .into_iter()
+            .flatten()
+            .for_each(|s| self.insert_series(s));
+        response
+            .songs
+            .into_iter()
+            .flatten()
+            .for_each(|s| self.insert_song(s));
+        response
+            .videos
+            .into_iter()
+            .flatten()
+            .for_each(|v| self.insert_video(v));
+    }
+
+    /// Inserts all entries and relations of a database dump
+    pub fn insert_dump(&mut self, data: DumpData) {
+        data.anime.into_iter().for_each(|a| self.insert_anime(a));
+        data.themes.into_iter().for_each(|t| self.insert_theme(t));
+        data.entries.into_iter().for_each(|e| self.insert_entry(e));
+        data.videos.into_iter().for_each(|v| self.insert_video(v));
+        data.songs.into_iter().for_each(|s| self.insert_song(s));
+        data.artists.into_iter().for_each(|a| self.insert_artist(a));
+
+        let relations = data.relations;
+        for (theme, anime) in relations.theme_anime {
+            self.anime_themes.link(anime, theme);
+        }
+        for (theme, song) in relations.theme_song {
+            self.song_themes.link(song, theme);
+        }
+        for (entry, theme) in relations.entry_theme {
+            self.theme_entries.link(theme, entry);
+        }
+        for (entry, video) in relations.entry_video {
+            self.entry_videos.link(entry, video);
+        }
+        for (artist, song) in relations.artist_song {
+            self.song_artists.link(song, artist);
+        }
+        for (artist, song, character) in relations.song_credits {
+            self.song_credits.insert((song, artist), character);
+        }
+    }
+
+    pub fn anime(&self, id: u32) -> Option<&Anime> {
+        self.anime.get(&id)
+    }
+
+    pub fn synonym(&self, id: u32) -> Option<&AnimeSynonym> {
+        self.synonyms.get(&id)
+    }
+
+    pub fn series(&self, id: u32) -> Option<&Series> {
+        self.series.get(&id)
+    }
+
+    pub fn theme(&self, id: u32) -> Option<&Theme> {
+        self.themes.get(&id)
+    }
+
+    pub fn entry(&self, id: u32) -> Option<&ThemeEntry> {
+        self.entries.get(&id)
+    }
+
+    pub fn video(&self, id: u32) -> Option<&Video> {
+        self.videos.get(&id)
+    }
+
+    /// Returns a video by its basename like `Bakemonogatari-OP1.webm`
+    pub fn video_by_basename(&self, basename: &str) -> Option<&Video> {
+        self.video_basenames
+            .get(basename)
+            .and_then(|id| self.videos.get(id))
+    }
+
+    pub fn song(&self, id: u32) -> Option<&Song> {
+        self.songs.get(&id)
+    }
+
+    pub fn artist(&self, id: u32) -> Option<&Artist> {
+        self.artists.get(&id)
+    }
+
+    pub fn all_anime(&self) -> impl Iterator<Item = &Anime> {
+        self.anime.values()
+    }
+
+    pub fn all_synonyms(&self) -> impl Iterator<Item = &AnimeSynonym> {
+        self.synonyms.values()
+    }
+
+    pub fn all_series(&self) -> impl Iterator<Item = &Series> {
+        self.series.values()
+    }
+
+    pub fn all_themes(&self) -> impl Iterator<Item = &Theme> {
+        self.themes.values()
+    }
+
+    pub fn all_entries(&self) -> impl Iterator<Item = &ThemeEntry> {
+        self.entries.values()
+    }
+
+    pub fn all_videos(&self) -> impl Iterator<Item = &Video> {
+        self.videos.values()
+    }
+
+    pub fn all_songs(&self) -> impl Iterator<Item = &Song> {
+        self.songs.values()
+    }
+
+    pub fn all_artists(&self) -> impl Iterator<Item = &Artist> {
+        self.artists.values()
+    }
+
+    /// Returns the synonyms of an anime
+    pub fn synonyms_of(&self, anime_id: u32) -> Vec<&AnimeSynonym> {
+        let synonyms = &self.synonyms;
+        lookup!(synonyms, self.anime_synonyms.forward(anime_id))
+    }
+
+    /// Returns the series an anime belongs to
+    pub fn series_of(&self, anime_id: u32) -> Vec<&Series> {
+        let series = &self.series;
+        lookup!(series, self.anime_series.forward(anime_id))
+    }
+
+    /// Returns the anime of a series
+    pub fn anime_of_series(&self, series_id: u32) -> Vec<&Anime> {
+        let anime = &self.anime;
+        lookup!(anime, self.anime_series.backward(series_id))
+    }
+
+    /// Returns the themes of an anime
+    pub fn themes_of(&self, anime_id: u32) -> Vec<&Theme> {
+        let themes = &self.themes;
+        lookup!(themes, self.anime_themes.forward(anime_id))
+    }
+
+    /// Returns the anime a theme belongs to
+    pub fn anime_of_theme(&self, theme_id: u32) -> Option<&Anime> {
+        self.anime_themes
+            .backward(theme_id)
+            .find_map(|id| self.anime.get(&id))
+    }
+
+    /// Returns the entries of a theme
+    pub fn entries_of(&self, theme_id: u32) -> Vec<&ThemeEntry> {
+        let entries = &self.entries;
+        lookup!(entries, self.theme_entries.forward(theme_id))
+    }
+
+    /// Returns the theme an entry belongs to
+    pub fn theme_of_entry(&self, entry_id: u32) -> Option<&Theme> {
+        self.theme_entries
+            .backward(entry_id)
+            .find_map(|id| self.themes.get(&id))
+    }
+
+    /// Returns the videos of an entry
+    pub fn videos_of(&self, entry_id: u32) -> Vec<&Video> {
+        let videos = &self.videos;
+        lookup!(videos, self.entry_videos.forward(entry_id))
+    }
+
+    /// Returns the entries a video is used for
+    pub fn entries_of_video(&self, video_id: u32) -> Vec<&ThemeEntry> {
+        let entries = &self.entries;
+        lookup!(entries, self.entry_videos.backward(video_id))
+    }
+
+    /// Returns the anime a video belongs to by the basename of the video.
+    /// A video can be used by entries of multiple anime.
+    pub fn anime_of_video(&self, basename: &str) -> Vec<&Anime> {
+        let Some(video_id) = self.video_basenames.get(basename) else {
+            return Vec::new();
+        };
+        let anime_ids: BTreeSet<u32> = self
+            .entry_videos
+            .backward(*video_id)
+            .flat_map(|entry| self.theme_entries.backward(entry))
+            .flat_map(|theme| self.anime_themes.backward(theme))
+            .collect();
+        let anime = &self.anime;
+
+        lookup!(anime, anime_ids.into_iter())
+    }
+
+    /// Returns the song of a theme
+    pub fn song_of(&self, theme_id: u32) -> Option<&Song> {
+        self.song_themes
+            .backward(theme_id)
+            .find_map(|id| self.songs.get(&id))
+    }
+
+    /// Returns the themes that use a song
+    pub fn themes_of_song(&self, song_id: u32) -> Vec<&Theme> {
+        let themes = &self.themes;
+        lookup!(themes, self.song_themes.forward(song_id))
+    }
+
+    /// Returns the artists performing a song
+    pub fn artists_of(&self, song_id: u32) -> Vec<&Artist> {
+        let artists = &self.artists;
+        lookup!(artists, self.song_artists.forward(song_id))
+    }
+
+    /// Returns the songs performed by an artist
+    pub fn songs_of(&self, artist_id: u32) -> Vec<&Song> {
+        let songs = &self.songs;
+        lookup!(songs, self.song_artists.backward(artist_id))
+    }
+
+    /// Returns the character an artist performs a song as if there is one
+    pub fn credited_character(&self, song_id: u32, artist_id: u32) -> Option<&str> {
+        self.song_credits
+            .get(&(song_id, artist_id))
+            .map(String::as_str)
+    }
+}
+
+/// Returns the id of an entry or None if the id is missing like in sparse fieldsets without `id`
+fn identify(kind: &str, meta: &EntryMetadata) -> Option<u32> {
+    if meta.id.is_none() {
+        tracing::debug!("skipping {kind} without id");
+    }
+
+    meta.id
+}
+
+/// Stores an entry, keeping the attributes of an already stored entry that the new one is missing.
+/// Returns the stored entry.
+fn store<T: Merge>(entries: &mut HashMap<u32, T>, id: u32, mut entry: T) -> &T {
+    if let Some(stored) = entries.remove(&id) {
+        entry.merge(stored);
+    }
+    entries.entry(id).or_insert(entry)
+}
+
+impl From<DumpData> for Catalogue {
+    fn from(data: DumpData) -> Self {
+        let mut catalogue = Self::new();
+        catalogue.insert_dump(data);
+
+        catalogue
+    }
+}
+
+impl From<SearchResponse> for Catalogue {
+    fn from(response: SearchResponse) -> Self {
+        let mut catalogue = Self::new();
+        catalogue.insert_search(response);
+
+        catalogue
+    }
+}
@@ -0,0 +1,239 @@
+//! Normalization of names for the local search.
+//! Kana is transliterated to hepburn romaji and diacritics are removed so that
+//! `トウキョウ`, `toukyou` and `Tōkyō` end up as `toukyou`, `toukyou` and `tokyo`.
+//! The [romaji_variant] additionally collapses long vowels in words that can be read
+//! as romaji, turning all of them into `tokyo` while english words like `book` are kept.
+
+/// Normalizes a text into lowercase words separated by single spaces
+pub(crate) fn normalize(text: &str) -> String {
+    let romaji = kana_to_romaji(text);
+    let mut normalized = String::with_capacity(romaji.len());
+
+    for c in romaji.chars().flat_map(char::to_lowercase) {
+        let c = fold_char(c);
+
+        if c.is_alphanumeric() {
+            normalized.push(c);
+        } else if !normalized.ends_with(' ') && !normalized.is_empty() {
+            normalized.push(' ');
+        }
+    }
+
+    normalized.trim_end().to_string()
+}
+
+/// Splits a normalized text into its words
+pub(crate) fn tokens(normalized: &str) -> Vec<&str> {
+    normalized.split(' ').filter(|t| !t.is_empty()).collect()
+}
+
+/// Collapses the long vowels of all words of a normalized text that can be read as romaji
+pub(crate) fn romaji_variant(normalized: &str) -> String {
+    tokens(normalized)
+        .into_iter()
+        .map(|word| {
+            if is_romaji(word) {
+                collapse_long_vowels(word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn collapse_long_vowels(text: &str) -> String {
+    text.replace("ou", "o")
+        .replace("oo", "o")
+        .replace("uu", "u")
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Returns if every consonant of the word can be part of a hepburn syllable,
+/// e.g. `toukyou` or `shakkin` but not `book` or `soul`
+fn is_romaji(word: &str) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+
+    chars.iter().enumerate().all(|(i, &c)| {
+        let next = chars.get(i + 1).copied();
+
+        match (c, next) {
+            (c, _) if is_vowel(c) => true,
+            // the syllabic n can be followed by anything
+            ('n', _) => true,
+            ('c', Some('h')) => true,
+            (
+                'k' | 'g' | 's' | 'z' | 't' | 'd' | 'h' | 'b' | 'p' | 'm' | 'r' | 'f' | 'j',
+                Some(n),
+            ) if is_vowel(n) || n == 'y' || n == c => true,
+            ('y' | 'w' | 'v', Some(n)) => is_vowel(n),
+            ('s', Some('h')) | ('t', Some('s')) | ('t', Some('c')) => true,
+            _ => false,
+        }
+    })
+}
+
+/// Maps full width and accented latin characters to their ascii counterpart
+fn fold_char(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        'ā' | 'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+        'ē' | 'é' | 'è' | 'ê' | 'ë' => 'e',
+        'ī' | 'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ō' | 'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' => 'o',
+        'ū' | 'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ñ' => 'n',
+        'ç' => 'c',
+        c => c,
+    }
+}
+
+/// Transliterates hiragana and katakana to hepburn romaji. Other characters are kept.
+fn kana_to_romaji(text: &str) -> String {
+    let mut romaji = String::with_capacity(text.len());
+    let mut double_next = false;
+
+    for c in text.chars() {
+        let hiragana = match c {
+            // katakana share the layout of hiragana
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            c => c,
+        };
+
+        match hiragana {
+            'っ' => double_next = true,
+            'ー' => {
+                if let Some(vowel) = romaji.chars().last().filter(|c| "aeiou".contains(*c)) {
+                    romaji.push(vowel);
+                }
+            }
+            'ゃ' | 'ゅ' | 'ょ' => {
+                let vowel = match hiragana {
+                    'ゃ' => 'a',
+                    'ゅ' => 'u',
+                    _ => 'o',
+                };
+
+                if romaji.ends_with("shi") || romaji.ends_with("chi") || romaji.ends_with("ji") {
+                    romaji.pop();
+                    romaji.push(vowel);
+                } else if romaji.ends_with('i') {
+                    romaji.pop();
+                    romaji.push('y');
+                    romaji.push(vowel);
+                } else {
+                    romaji.push('y');
+                    romaji.push(vowel);
+                }
+            }
+            c => match syllable(c) {
+                Some(syllable) => {
+                    if double_next {
+                        let consonant = if syllable.starts_with("ch") {
+                            't'
+                        } else {
+                            syllable.chars().next().unwrap_or_default()
+                        };
+
+                        if !"aeiou".contains(consonant) {
+                            romaji.push(consonant);
+                        }
+                        double_next = false;
+                    }
+                    romaji.push_str(syllable);
+                }
+                None => {
+                    double_next = false;
+                    romaji.push(c);
+                }
+            },
+        }
+    }
+
+    romaji
+}
+
+fn syllable(c: char) -> Option<&'static str> {
+    let romaji = match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' => "e",
+        'お' | 'ぉ' => "o",
+        'か' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' => "ji",
+        'ず' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'ぢ' => "ji",
+        'づ' => "zu",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' => "ya",
+        'ゆ' => "yu",
+        'よ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ゐ' => "wi",
+        'ゑ' => "we",
+        'を' => "wo",
+        'ん' => "n",
+        'ゔ' => "vu",
+        _ => return None,
+    };
+
+    Some(romaji)
+}
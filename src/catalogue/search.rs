@@ -0,0 +1,373 @@
+use crate::catalogue::normalize::{normalize, romaji_variant, tokens};
+use crate::catalogue::Catalogue;
+use crate::models::{Anime, AnimeSynonym, Artist, SearchResponse, Series, Song, Theme, Video};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Matches of the romaji variants rank slightly below matches of the texts themselves
+const VARIANT_PENALTY: f32 = 0.95;
+
+/// A text normalized once when its entry is inserted
+#[derive(Clone, Debug)]
+pub(super) struct Field {
+    text: String,
+    variant: String,
+    /// the words of the text with their romaji variant
+    words: Vec<(String, String)>,
+}
+
+impl Field {
+    fn new(text: &str) -> Self {
+        let text = normalize(text);
+        let variant = romaji_variant(&text);
+        let words = tokens(&text)
+            .into_iter()
+            .zip(tokens(&variant))
+            .map(|(word, variant)| (word.to_string(), variant.to_string()))
+            .collect();
+
+        Self {
+            text,
+            variant,
+            words,
+        }
+    }
+
+    /// Joins two fields as if their texts were normalized together
+    fn join(&self, other: &Field) -> Self {
+        Self {
+            text: format!("{} {}", self.text, other.text),
+            variant: format!("{} {}", self.variant, other.variant),
+            words: self.words.iter().chain(&other.words).cloned().collect(),
+        }
+    }
+}
+
+/// The texts an entry can be found by
+pub(super) trait Searchable {
+    fn texts(&self) -> Vec<&str>;
+}
+
+macro_rules! searchable {
+    ($($type: ty => |$entry: ident| $texts: expr),*) => {
+        $(
+            impl Searchable for $type {
+                fn texts(&self) -> Vec<&str> {
+                    let $entry = self;
+                    $texts.into_iter().filter_map(|text| text.as_deref()).collect()
+                }
+            }
+        )*
+    };
+}
+
+searchable! {
+    Anime => |anime| [&anime.name],
+    AnimeSynonym => |synonym| [&synonym.text],
+    Series => |series| [&series.name],
+    Theme => |theme| [&theme.slug],
+    Video => |video| [&video.filename, &video.basename],
+    Song => |song| [&song.title],
+    Artist => |artist| [&artist.name]
+}
+
+/// The normalized fields of stored entries by id
+pub(super) type Fields = HashMap<u32, Vec<Field>>;
+
+/// The normalized fields of all searchable entries of a [Catalogue] per type
+#[derive(Clone, Debug, Default)]
+pub(super) struct SearchIndex {
+    pub anime: Fields,
+    pub synonyms: Fields,
+    pub series: Fields,
+    pub themes: Fields,
+    pub videos: Fields,
+    pub songs: Fields,
+    pub artists: Fields,
+}
+
+/// Normalizes the texts of a stored entry, replacing the fields of its previous version
+pub(super) fn index<T: Searchable>(fields: &mut Fields, id: u32, entry: &T) {
+    let normalized = entry
+        .texts()
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .map(Field::new)
+        .collect();
+    fields.insert(id, normalized);
+}
+
+/// Returns the fields of an entry
+fn fields(fields: &Fields, id: u32) -> impl Iterator<Item = &Field> {
+    fields.get(&id).into_iter().flatten()
+}
+
+/// A candidate of the local search with the fields it can be found by
+struct Document<'a, T> {
+    entry: &'a T,
+    fields: Vec<Cow<'a, Field>>,
+}
+
+impl<'a, T> Document<'a, T> {
+    fn new(entry: &'a T, fields: impl IntoIterator<Item = &'a Field>) -> Self {
+        Self {
+            entry,
+            fields: fields.into_iter().map(Cow::Borrowed).collect(),
+        }
+    }
+}
+
+/// A query prepared for matching against documents
+struct Query {
+    field: Field,
+}
+
+impl Query {
+    fn new(query: &str) -> Self {
+        Self {
+            field: Field::new(query),
+        }
+    }
+
+    /// Scores a document by matching every query token against the words of its fields.
+    /// Words are compared as they are and as romaji variants, so english words
+    /// don't lose their long vowels. Returns None if one of the tokens doesn't match at all.
+    fn score(&self, fields: &[Cow<Field>]) -> Option<f32> {
+        let words: Vec<&(String, String)> = fields.iter().flat_map(|f| &f.words).collect();
+        let mut total = 0.0;
+
+        for (token, token_variant) in &self.field.words {
+            let best = words
+                .iter()
+                .map(|(word, variant)| {
+                    let score = token_score(token, word);
+
+                    if token == token_variant && word == variant {
+                        return score;
+                    }
+                    score.max(token_score(token_variant, variant) * VARIANT_PENALTY)
+                })
+                .fold(0.0, f32::max);
+
+            if best <= 0.0 {
+                return None;
+            }
+            total += best;
+        }
+        let mut score = total / self.field.words.len() as f32;
+        let query = &self.field;
+
+        // prefer entries where the query matches a whole name over scattered words
+        if fields.iter().any(|f| f.text == query.text) {
+            score += 1.0;
+        } else if fields.iter().any(|f| f.variant == query.variant) {
+            score += VARIANT_PENALTY;
+        } else if fields.iter().any(|f| f.text.starts_with(&query.text)) {
+            score += 0.5;
+        }
+
+        Some(score)
+    }
+
+    /// Returns the best matching documents ordered by their score
+    fn rank<'a, T: Clone>(&self, documents: Vec<Document<'a, T>>, limit: usize) -> Vec<T> {
+        let mut scored: Vec<(f32, &str, &'a T)> = documents
+            .iter()
+            .filter_map(|document| {
+                let score = self.score(&document.fields)?;
+
+                Some((
+                    score,
+                    document.fields.first()?.text.as_str(),
+                    document.entry,
+                ))
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.1.cmp(b.1))
+        });
+
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, entry)| entry.clone())
+            .collect()
+    }
+}
+
+/// Scores how well a query token matches a word between 0 and 1
+fn token_score(token: &str, word: &str) -> f32 {
+    if token == word {
+        return 1.0;
+    }
+    if word.starts_with(token) {
+        return 0.9;
+    }
+    let length = token.chars().count();
+    let max_typos = match length {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+
+    if max_typos > 0 {
+        if let Some(typos) = edit_distance(token, word, max_typos) {
+            return 0.8 - 0.2 * (typos - 1) as f32;
+        }
+        // the word might still be typed
+        let prefix: String = word.chars().take(length).collect();
+
+        if let Some(typos) = edit_distance(token, &prefix, max_typos) {
+            return 0.6 - 0.2 * (typos - 1) as f32;
+        }
+    }
+    if length >= 3 && word.contains(token) {
+        return 0.5;
+    }
+
+    0.0
+}
+
+/// Returns the number of edits including transpositions needed to turn a into b
+/// or None if it exceeds the maximum
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous2: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(previous2[j - 2] + 1);
+            }
+        }
+        if current.iter().min().copied().unwrap_or_default() > max {
+            return None;
+        }
+        previous2 = std::mem::replace(&mut previous, current);
+    }
+
+    Some(previous[b.len()]).filter(|distance| *distance <= max)
+}
+
+impl Catalogue {
+    /// Searches the catalogue for anime, themes, artists, series, songs and videos
+    /// similar to the query without accessing the api.
+    /// Anime are also found by their synonyms and themes by the name of their anime,
+    /// their slug and the title of their song.
+    /// Up to `limit` results are returned per type, ordered by relevance.
+    ///
+    /// ```
+    /// use animethemes_rs::catalogue::Catalogue;
+    ///
+    /// let catalogue = Catalogue::new();
+    /// let response = catalogue.search("bakemonogatari", 15);
+    /// assert!(response.anime.unwrap().is_empty());
+    /// ```
+    pub fn search(&self, query: &str, limit: usize) -> SearchResponse {
+        let query = Query::new(query);
+
+        if query.field.words.is_empty() {
+            return SearchResponse::default();
+        }
+        let index = &self.search_index;
+
+        let anime = self
+            .anime
+            .iter()
+            .map(|(&id, anime)| {
+                let synonyms = self
+                    .anime_synonyms
+                    .forward(id)
+                    .flat_map(|synonym| fields(&index.synonyms, synonym));
+
+                Document::new(anime, fields(&index.anime, id).chain(synonyms))
+            })
+            .collect();
+        let themes = self
+            .themes
+            .iter()
+            .map(|(&id, theme)| {
+                let slug = index.themes.get(&id).and_then(|f| f.first());
+                let anime = self
+                    .anime_themes
+                    .backward(id)
+                    .find_map(|anime| index.anime.get(&anime))
+                    .and_then(|f| f.first());
+                let song = self
+                    .song_themes
+                    .backward(id)
+                    .find_map(|song| index.songs.get(&song))
+                    .and_then(|f| f.first());
+                let mut fields: Vec<Cow<Field>> = Vec::new();
+
+                if let (Some(anime), Some(slug)) = (anime, slug) {
+                    fields.push(Cow::Owned(anime.join(slug)));
+                }
+                fields.extend(song.into_iter().chain(slug).map(Cow::Borrowed));
+
+                Document {
+                    entry: theme,
+                    fields,
+                }
+            })
+            .collect();
+        let artists = self
+            .artists
+            .iter()
+            .map(|(&id, artist)| Document::new(artist, fields(&index.artists, id)))
+            .collect();
+        let series = self
+            .series
+            .iter()
+            .map(|(&id, series)| Document::new(series, fields(&index.series, id)))
+            .collect();
+        let songs = self
+            .songs
+            .iter()
+            .map(|(&id, song)| Document::new(song, fields(&index.songs, id)))
+            .collect();
+        let videos = self
+            .videos
+            .iter()
+            .map(|(&id, video)| Document::new(video, fields(&index.videos, id)))
+            .collect();
+
+        let themes = query
+            .rank(themes, limit)
+            .into_iter()
+            .map(|mut theme| {
+                // stored themes always have an id
+                if let Some(id) = theme.meta.id {
+                    theme.anime = self.anime_of_theme(id).cloned();
+                    theme.song = self.song_of(id).cloned();
+                }
+                theme
+            })
+            .collect();
+
+        SearchResponse {
+            anime: Some(query.rank(anime, limit)),
+            artists: Some(query.rank(artists, limit)),
+            series: Some(query.rank(series, limit)),
+            songs: Some(query.rank(songs, limit)),
+            themes: Some(themes),
+            videos: Some(query.rank(videos, limit)),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::cache::ResponseCache;
+use crate::client::{AnimeThemesClient, Token, DEFAULT_API_ENDPOINT, DEFAULT_VIDEO_ENDPOINT};
+use crate::error::ApiResult;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::transport::Transport;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use reqwest::Proxy;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub static DEFAULT_USER_AGENT: &str = concat!("animethemes-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Builder to configure an [AnimeThemesClient]
+///
+/// ```
+/// # use animethemes_rs::error::ApiResult;
+/// use animethemes_rs::client::AnimeThemesClientBuilder;
+/// use std::time::Duration;
+///
+/// # fn a() -> ApiResult<()> {
+/// let client = AnimeThemesClientBuilder::default()
+///     .user_agent("my-service/1.0")
+///     .timeout(Duration::from_secs(10))
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AnimeThemesClientBuilder {
+    api_endpoint: Option<String>,
+    video_endpoint: Option<String>,
+    client: Option<reqwest::Client>,
+    headers: HeaderMap,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    token: Option<Token>,
+    cache: Option<ResponseCache>,
+    rate_limit: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl AnimeThemesClientBuilder {
+    /// Sets the base url of the api
+    pub fn api_endpoint<S: ToString>(mut self, endpoint: S) -> Self {
+        self.api_endpoint = Some(endpoint.to_string());
+
+        self
+    }
+
+    /// Sets the base url videos are served from
+    pub fn video_endpoint<S: ToString>(mut self, endpoint: S) -> Self {
+        self.video_endpoint = Some(endpoint.to_string());
+
+        self
+    }
+
+    /// Uses an existing reqwest client for all requests.
+    /// The default headers and user agent of this builder are still added to every request,
+    /// while the timeouts and proxy need to be configured on the given client instead.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+
+        self
+    }
+
+    /// Adds a header that is sent with every request
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+
+        self
+    }
+
+    /// Adds multiple headers that are sent with every request
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+
+        self
+    }
+
+    /// Sets the user agent. Defaults to [DEFAULT_USER_AGENT]
+    pub fn user_agent<S: ToString>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+
+        self
+    }
+
+    /// Sets the timeout for a complete request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Routes all requests through the given proxy
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+
+        self
+    }
+
+    /// Authenticates all api requests with the given sanctum token.
+    /// A token is required for write endpoints and endpoints of the current user.
+    pub fn token<S: ToString>(mut self, token: S) -> Self {
+        self.token = Some(Token(token.to_string()));
+
+        self
+    }
+
+    /// Caches the responses of get requests to the api
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+
+        self
+    }
+
+    /// Limits the rate of requests. Requests aren't limited unless a limiter is set.
+    /// [RateLimiter::default] allows the number of requests per minute permitted by the api.
+    /// Passing the limiter of another client makes both clients share the limit.
+    pub fn rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limit = Some(limiter);
+
+        self
+    }
+
+    /// Sets the policy for retrying failed requests. Defaults to [RetryPolicy::default].
+    /// Use [RetryPolicy::none] to send every request only once.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+
+        self
+    }
+
+    /// Sends all requests through the given transport instead of the reqwest client.
+    /// The default headers and user agent of this builder are added to every request
+    /// while the timeouts and proxy only apply to the reqwest client.
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+
+        self
+    }
+
+    /// Builds the client
+    pub fn build(self) -> ApiResult<AnimeThemesClient> {
+        let user_agent = self
+            .user_agent
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        let mut headers = self.headers;
+        // an invalid user agent is reported when building the reqwest client
+        if let Ok(value) = HeaderValue::from_str(&user_agent) {
+            headers.insert(USER_AGENT, value);
+        }
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder()
+                    .default_headers(headers.clone())
+                    .user_agent(user_agent);
+
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+
+                builder.build()?
+            }
+        };
+
+        Ok(AnimeThemesClient {
+            api_endpoint: self
+                .api_endpoint
+                .unwrap_or_else(|| DEFAULT_API_ENDPOINT.to_string()),
+            video_endpoint: self
+                .video_endpoint
+                .unwrap_or_else(|| DEFAULT_VIDEO_ENDPOINT.to_string()),
+            transport: self.transport.unwrap_or_else(|| Arc::new(client.clone())),
+            client,
+            headers,
+            token: self.token,
+            cache: self.cache,
+            rate_limit: self.rate_limit,
+            retry: self.retry.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use crate::client::{missing_attribute, AnimeThemesClient};
+use crate::dump::DownloadProgress;
+use crate::error::ApiResult;
+use crate::fields::Fieldsets;
+use crate::filters::DumpFilter;
+use crate::models::Dump;
+use crate::pagination::Pagination;
+use crate::sort::{DumpSort, Sort};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+impl AnimeThemesClient {
+    /// Returns the newest database dump matching the filter
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn latest_dump(&self, filter: DumpFilter) -> ApiResult<Option<Dump>> {
+        let page = self
+            .list_dumps(
+                Pagination::new(1, 1),
+                filter,
+                Sort::desc(DumpSort::CreatedAt),
+                Fieldsets::default(),
+            )
+            .await?;
+
+        Ok(page.data.into_iter().next())
+    }
+
+    /// Downloads a database dump into the writer and reports the progress after each chunk.
+    /// Returns the number of bytes written.
+    #[tracing::instrument(level = "debug", skip(self, dump, writer, progress), fields(link = ?dump.link))]
+    pub async fn download_dump<W, F>(
+        &self,
+        dump: &Dump,
+        writer: &mut W,
+        mut progress: F,
+    ) -> ApiResult<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(DownloadProgress),
+    {
+        let link = dump
+            .link
+            .as_deref()
+            .ok_or_else(|| missing_attribute("link"))?;
+        let mut response = self.get(link, &()).await?;
+        let total = response.content_length();
+        let mut downloaded = 0u64;
+
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+            progress(DownloadProgress { downloaded, total });
+        }
+        writer.flush().await?;
+
+        Ok(downloaded)
+    }
+
+    /// Downloads the newest database dump matching the filter into the writer.
+    /// Returns the downloaded dump or None if there's no dump matching the filter.
+    #[tracing::instrument(level = "debug", skip(self, writer, progress))]
+    pub async fn download_latest_dump<W, F>(
+        &self,
+        filter: DumpFilter,
+        writer: &mut W,
+        progress: F,
+    ) -> ApiResult<Option<Dump>>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(DownloadProgress),
+    {
+        match self.latest_dump(filter).await? {
+            Some(dump) => {
+                self.download_dump(&dump, writer, progress).await?;
+                Ok(Some(dump))
+            }
+            None => Ok(None),
+        }
+    }
+}
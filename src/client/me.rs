@@ -0,0 +1,38 @@
+use crate::client::AnimeThemesClient;
+use crate::error::ApiResult;
+use crate::fields::Fieldsets;
+use crate::filters::{Filters, PlaylistFilter};
+use crate::includes::*;
+use crate::models::{Page, Playlist, User};
+use crate::pagination::Pagination;
+use crate::sort::{PlaylistSort, Sort};
+
+impl AnimeThemesClient {
+    /// Returns the user the configured token belongs to
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn me(&self, include: UserInclude, fields: Fieldsets) -> ApiResult<User> {
+        self.show_with_include("/me", "user", include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a page of the playlists of the user the configured token belongs to,
+    /// including private and unlisted playlists
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn my_playlists(
+        &self,
+        pagination: Pagination,
+        include: PlaylistInclude,
+        filter: PlaylistFilter,
+        sort: Sort<PlaylistSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Playlist>> {
+        self.index_with_include(
+            "me/playlist",
+            "playlists",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+}
@@ -0,0 +1,876 @@
+use crate::cache::{CacheStats, CachedResponse, ResponseCache};
+use crate::error::{ApiError, ApiResult};
+use crate::fields::Fieldsets;
+use crate::filters::*;
+use crate::includes::*;
+use crate::models::{
+    Anime, AnimeSynonym, Artist, Audio, Image, Page, PageLinks, PageMeta, Resource, SearchResponse,
+    Series, Song, Studio, Theme, ThemeEntry, Video, VideoScript,
+};
+use crate::pagination::Pagination;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::sort::*;
+use crate::transport::Transport;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Request, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+
+mod builder;
+mod dumps;
+mod me;
+mod paginate;
+mod playlists;
+mod wiki;
+
+pub use builder::*;
+
+pub static DEFAULT_API_ENDPOINT: &str = "https://api.animethemes.moe";
+pub static DEFAULT_VIDEO_ENDPOINT: &str = "https://animethemes.moe/video/";
+
+#[derive(Clone, Debug)]
+pub struct AnimeThemesClient {
+    api_endpoint: String,
+    pub video_endpoint: String,
+    client: reqwest::Client,
+    headers: HeaderMap,
+    transport: Arc<dyn Transport>,
+    token: Option<Token>,
+    cache: Option<ResponseCache>,
+    rate_limit: Option<RateLimiter>,
+    retry: RetryPolicy,
+}
+
+impl Default for AnimeThemesClient {
+    fn default() -> Self {
+        Self::builder()
+            .build()
+            .expect("failed to build the default client")
+    }
+}
+
+impl AnimeThemesClient {
+    /// Creates a new AnimeThemesClient
+    /// Use [AnimeThemesClient::builder] for further configuration
+    pub fn new(api_endpoint: &str, video_endpoint: &str) -> Self {
+        Self::builder()
+            .api_endpoint(api_endpoint)
+            .video_endpoint(video_endpoint)
+            .build()
+            .expect("failed to build the client")
+    }
+
+    /// Returns a builder to configure a new client
+    pub fn builder() -> AnimeThemesClientBuilder {
+        AnimeThemesClientBuilder::default()
+    }
+
+    /// Returns the rate limiter used by the client if rate limiting is enabled
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limit.as_ref()
+    }
+
+    /// Returns the hit and miss counters of the response cache if one is configured
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(ResponseCache::stats)
+    }
+
+    /// Searches for all types provided by the api
+    /// fields and include can be used to control what to include in the results
+    /// if no fields are specified, all are returned.
+    /// The `search` fieldset selects the types of results
+    /// filter narrows down the results of each type and sort controls their order
+    /// This crate provides a client for the AnimeThemes.moe api.
+    ///
+    /// ```
+    /// # use animethemes_rs::error::ApiResult;
+    /// use animethemes_rs::client::AnimeThemesClient;
+    /// use animethemes_rs::fields::Fieldsets;
+    /// use animethemes_rs::filters::SearchFilters;
+    /// use animethemes_rs::includes::SearchIncludes;
+    /// use animethemes_rs::sort::SearchSorts;
+    ///
+    /// # async fn a() -> ApiResult<()> {
+    /// let client = AnimeThemesClient::default();
+    /// let response = client
+    ///     .search(
+    ///         "Attack on Titan",
+    ///         Fieldsets::default(),
+    ///         SearchIncludes::default(),
+    ///         SearchFilters::default(),
+    ///         SearchSorts::default(),
+    ///     )
+    ///     .await?;
+    ///
+    /// assert!(response.anime.is_some());
+    /// assert!(response.songs.is_some());
+    /// # Ok(()) }
+    /// ```
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn search(
+        &self,
+        query: &str,
+        fields: Fieldsets,
+        include: SearchIncludes,
+        filter: SearchFilters,
+        sort: SearchSorts,
+    ) -> ApiResult<SearchResponse> {
+        let mut query = vec![("q".to_string(), query.to_string())];
+        query.append(&mut include.indo_includes());
+        query.append(&mut filter.query());
+        query.append(&mut sort.query());
+        query.append(&mut fields.query());
+        take_envelope(self.api_get_json("/search", &query[..]).await?, "search")
+    }
+
+    /// Returns an anime by a given slug string
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn anime(
+        &self,
+        slug: &str,
+        include: AnimeInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Anime> {
+        self.entry_by_id_with_include("anime", slug, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns an artist by a given slug string
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn artist(
+        &self,
+        slug: &str,
+        include: ArtistInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Artist> {
+        self.entry_by_id_with_include("artist", slug, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns an audio by basename
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn audio(
+        &self,
+        basename: &str,
+        include: AudioInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Audio> {
+        self.entry_by_id_with_include("audio", basename, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns an entry by a given id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn entry(
+        &self,
+        id: u32,
+        include: ThemeEntryInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<ThemeEntry> {
+        self.entry_by_id_with_include("animethemeentry", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns an image by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn image(
+        &self,
+        id: u32,
+        include: ImageInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Image> {
+        self.entry_by_id_with_include("image", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a resource by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn resource(
+        &self,
+        id: u32,
+        include: ResourceInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Resource> {
+        self.entry_by_id_with_include("resource", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a series by slug
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn series(
+        &self,
+        slug: &str,
+        include: SeriesInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Series> {
+        self.entry_by_id_with_include("series", slug, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a song by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn song(&self, id: u32, include: SongInclude, fields: Fieldsets) -> ApiResult<Song> {
+        self.entry_by_id_with_include("song", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a studio by slug
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn studio(
+        &self,
+        slug: &str,
+        include: StudioInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Studio> {
+        self.entry_by_id_with_include("studio", slug, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a synonym by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn synonym(
+        &self,
+        id: u32,
+        include: SynonymInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<AnimeSynonym> {
+        self.entry_by_id_with_include("animesynonym", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a theme by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn theme(
+        &self,
+        id: u32,
+        include: ThemeInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Theme> {
+        self.entry_by_id_with_include("animetheme", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a video by basename
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn video(
+        &self,
+        basename: &str,
+        include: VideoInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Video> {
+        self.entry_by_id_with_include("video", basename, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a page of anime
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_anime(
+        &self,
+        pagination: Pagination,
+        include: AnimeInclude,
+        filter: AnimeFilter,
+        sort: Sort<AnimeSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Anime>> {
+        self.index_with_include(
+            "anime",
+            "anime",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of artists
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_artists(
+        &self,
+        pagination: Pagination,
+        include: ArtistInclude,
+        filter: ArtistFilter,
+        sort: Sort<ArtistSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Artist>> {
+        self.index_with_include(
+            "artist",
+            "artists",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of audios
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_audio(
+        &self,
+        pagination: Pagination,
+        include: AudioInclude,
+        filter: AudioFilter,
+        sort: Sort<AudioSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Audio>> {
+        self.index_with_include(
+            "audio",
+            "audios",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of theme entries
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_entries(
+        &self,
+        pagination: Pagination,
+        include: ThemeEntryInclude,
+        filter: ThemeEntryFilter,
+        sort: Sort<ThemeEntrySort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<ThemeEntry>> {
+        self.index_with_include(
+            "animethemeentry",
+            "animethemeentries",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of images
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_images(
+        &self,
+        pagination: Pagination,
+        include: ImageInclude,
+        filter: ImageFilter,
+        sort: Sort<ImageSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Image>> {
+        self.index_with_include(
+            "image",
+            "images",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of resources
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_resources(
+        &self,
+        pagination: Pagination,
+        include: ResourceInclude,
+        filter: ResourceFilter,
+        sort: Sort<ResourceSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Resource>> {
+        self.index_with_include(
+            "resource",
+            "resources",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of series
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_series(
+        &self,
+        pagination: Pagination,
+        include: SeriesInclude,
+        filter: SeriesFilter,
+        sort: Sort<SeriesSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Series>> {
+        self.index_with_include(
+            "series",
+            "series",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of songs
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_songs(
+        &self,
+        pagination: Pagination,
+        include: SongInclude,
+        filter: SongFilter,
+        sort: Sort<SongSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Song>> {
+        self.index_with_include(
+            "song",
+            "songs",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of studios
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_studios(
+        &self,
+        pagination: Pagination,
+        include: StudioInclude,
+        filter: StudioFilter,
+        sort: Sort<StudioSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Studio>> {
+        self.index_with_include(
+            "studio",
+            "studios",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of synonyms
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_synonyms(
+        &self,
+        pagination: Pagination,
+        include: SynonymInclude,
+        filter: SynonymFilter,
+        sort: Sort<SynonymSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<AnimeSynonym>> {
+        self.index_with_include(
+            "animesynonym",
+            "animesynonyms",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of themes
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_themes(
+        &self,
+        pagination: Pagination,
+        include: ThemeInclude,
+        filter: ThemeFilter,
+        sort: Sort<ThemeSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Theme>> {
+        self.index_with_include(
+            "animetheme",
+            "animethemes",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a page of videos
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_videos(
+        &self,
+        pagination: Pagination,
+        include: VideoInclude,
+        filter: VideoFilter,
+        sort: Sort<VideoSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Video>> {
+        self.index_with_include(
+            "video",
+            "videos",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a video script by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn video_script(
+        &self,
+        id: u32,
+        include: VideoScriptInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<VideoScript> {
+        self.entry_by_id_with_include("videoscript", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a page of video scripts
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_video_scripts(
+        &self,
+        pagination: Pagination,
+        include: VideoScriptInclude,
+        filter: VideoScriptFilter,
+        sort: Sort<VideoScriptSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<VideoScript>> {
+        self.index_with_include(
+            "videoscript",
+            "videoscripts",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Downloads the content of a video script from its link
+    #[tracing::instrument(level = "debug", skip(self, script), fields(link = ?script.link))]
+    pub async fn download_video_script(&self, script: &VideoScript) -> ApiResult<String> {
+        let link = script
+            .link
+            .as_deref()
+            .ok_or_else(|| missing_attribute("link"))?;
+        let text = self.get(link, &()).await?.text().await?;
+
+        Ok(text)
+    }
+
+    /// Generic endpoint with the format /<endpoint>/<id> returning the type on the json field <endpoint>
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn entry_by_id_with_include<T: DeserializeOwned, I: Display + Debug>(
+        &self,
+        endpoint: &str,
+        id: I,
+        include: Vec<String>,
+        params: Vec<(String, String)>,
+    ) -> ApiResult<T> {
+        self.show_with_include(
+            format!("/{}/{}", endpoint, id).as_str(),
+            endpoint,
+            include,
+            params,
+        )
+        .await
+    }
+
+    /// Generic endpoint returning a single entry of the type on the json field <key>
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn show_with_include<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        key: &str,
+        include: Vec<String>,
+        mut params: Vec<(String, String)>,
+    ) -> ApiResult<T> {
+        let mut query = Vec::new();
+
+        if !include.is_empty() {
+            query.push(("include".to_string(), include.join(",")));
+        }
+        query.append(&mut params);
+        take_envelope(self.api_get_json(path, &query[..]).await?, key)
+    }
+
+    /// Generic index endpoint with the format /<endpoint> returning a list of the type
+    /// on the json field <key> next to the pagination links and meta
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn index_with_include<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        key: &str,
+        pagination: Pagination,
+        include: Vec<String>,
+        mut params: Vec<(String, String)>,
+    ) -> ApiResult<Page<T>> {
+        let mut query = pagination.query();
+
+        if !include.is_empty() {
+            query.push(("include".to_string(), include.join(",")));
+        }
+        query.append(&mut params);
+        let response: IndexResponse<T> = self
+            .api_get_json(format!("/{}", endpoint).as_str(), &query[..])
+            .await?;
+
+        response.into_page(key)
+    }
+
+    /// Requests a json response from the API endpoint.
+    /// The response is served from the cache if one is configured.
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn api_get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(String, String)],
+    ) -> ApiResult<T> {
+        let body = match &self.cache {
+            Some(cache) => self.api_get_cached(cache, path, query).await?,
+            None => self.api_get(path, query).await?.text().await?,
+        };
+
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Returns the cached response body if it's still fresh.
+    /// Otherwise the response is requested again, revalidating the cached response if possible.
+    async fn api_get_cached(
+        &self,
+        cache: &ResponseCache,
+        path: &str,
+        query: &[(String, String)],
+    ) -> ApiResult<String> {
+        let token = self.token.as_ref().map(|token| token.0.as_str());
+        let key = ResponseCache::key(&self.api_url(path), query, token);
+        let cached = cache.get(&key);
+        let mut request = self.client.get(self.api_url(path)).query(query);
+
+        if let Some(cached) = &cached {
+            if cached.age() < cache.ttl_for(path) {
+                tracing::trace!("cache hit for {key}");
+                cache.record_hit();
+                return Ok(cached.body.clone());
+            }
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = self.send(self.authenticate(request)).await?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(mut cached)) = (response.status(), cached) {
+            tracing::trace!("revalidated cached response for {key}");
+            cache.record_revalidation();
+            cached.refresh();
+            cache.put(&key, cached.clone());
+
+            return Ok(cached.body);
+        }
+        cache.record_miss();
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response.text().await?;
+        cache.put(&key, CachedResponse::new(body.clone(), etag, last_modified));
+
+        Ok(body)
+    }
+
+    /// Starts a get request to the API endpoint
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn api_get<T: Serialize + Debug + ?Sized>(
+        &self,
+        path: &str,
+        query: &T,
+    ) -> ApiResult<Response> {
+        let request = self.client.get(self.api_url(path)).query(query);
+
+        self.send(self.authenticate(request)).await
+    }
+
+    /// Starts a post request with a json body to the API endpoint
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn api_post<B: Serialize + Debug + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> ApiResult<Response> {
+        let request = self.client.post(self.api_url(path)).json(body);
+
+        self.send(self.authenticate(request)).await
+    }
+
+    /// Starts a put request with a json body to the API endpoint
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn api_put<B: Serialize + Debug + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> ApiResult<Response> {
+        let request = self.client.put(self.api_url(path)).json(body);
+
+        self.send(self.authenticate(request)).await
+    }
+
+    /// Starts a delete request to the API endpoint
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn api_delete(&self, path: &str) -> ApiResult<Response> {
+        let request = self.client.delete(self.api_url(path));
+
+        self.send(self.authenticate(request)).await
+    }
+
+    /// Starts a get request to the given url
+    /// The token is never sent with these requests as the url might belong to a different host
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get<T: Serialize + Debug + ?Sized>(
+        &self,
+        url: &str,
+        query: &T,
+    ) -> ApiResult<Response> {
+        self.send(self.client.get(url).query(query)).await
+    }
+
+    /// Sends a request and retries it according to the retry policy.
+    /// Unsuccessful responses are converted into the matching [ApiError].
+    /// `304 Not Modified` is only passed through for conditional requests revalidating cached responses.
+    async fn send(&self, request: RequestBuilder) -> ApiResult<Response> {
+        let mut request = request.build()?;
+        // the reqwest client only adds its default headers when it sends the request itself
+        for (name, value) in &self.headers {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+        let mut attempt = 1;
+
+        loop {
+            let retry = if self.retry.allows_retry(request.method(), attempt) {
+                request.try_clone()
+            } else {
+                None
+            };
+            tracing::trace!(attempt, url = %request.url(), "sending request");
+
+            match (self.execute(request).await, retry) {
+                (Err(error), Some(retry)) if self.retry.is_retryable(&error) => {
+                    let Some(delay) = self.retry.delay(attempt, &error) else {
+                        tracing::warn!(attempt, "retry after exceeds the max backoff: {error}");
+                        return Err(error);
+                    };
+                    tracing::warn!(attempt, ?delay, "request failed, retrying: {error}");
+                    tokio::time::sleep(delay).await;
+                    request = retry;
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    /// Sends a request once.
+    /// If rate limiting is enabled, the request waits until the limiter allows it.
+    async fn execute(&self, request: Request) -> ApiResult<Response> {
+        if let Some(limiter) = &self.rate_limit {
+            limiter.acquire().await;
+        }
+        let conditional = request.headers().contains_key(IF_NONE_MATCH)
+            || request.headers().contains_key(IF_MODIFIED_SINCE);
+        let response = self.transport.execute(request).await?;
+
+        if let Some(limiter) = &self.rate_limit {
+            limiter.update(response.headers());
+        }
+
+        if response.status().is_success()
+            || (conditional && response.status() == StatusCode::NOT_MODIFIED)
+        {
+            Ok(response)
+        } else {
+            Err(ApiError::from_response(response).await)
+        }
+    }
+
+    /// Adds the bearer token to the request if one is configured
+    fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(&token.0),
+            None => request,
+        }
+    }
+
+    /// Evicts the cached responses containing playlists after a playlist or track was written.
+    /// Next to `/playlist` that's `/me`, which includes the playlists of the user.
+    fn invalidate_playlists(&self) {
+        if let Some(cache) = &self.cache {
+            for path in ["/playlist", "/me"] {
+                tracing::trace!("invalidating cached responses of {path}");
+                cache.invalidate(&self.api_url(path));
+            }
+        }
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api_endpoint, path)
+    }
+}
+
+/// Removes the type from the json field <key> of a response
+async fn envelope<T: DeserializeOwned>(response: Response, key: &str) -> ApiResult<T> {
+    take_envelope(response.json().await?, key)
+}
+
+/// Returns the json field <key> of a deserialized response
+fn take_envelope<T>(mut response: HashMap<String, T>, key: &str) -> ApiResult<T> {
+    response
+        .remove(key)
+        .ok_or_else(|| ApiError::MissingEnvelope {
+            key: key.to_string(),
+        })
+}
+
+pub(crate) fn missing_attribute(name: &str) -> ApiError {
+    ApiError::MissingAttribute {
+        name: name.to_string(),
+    }
+}
+
+/// The api token. Kept in a separate type so it doesn't show up in debug output
+#[derive(Clone)]
+struct Token(String);
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Token(***)")
+    }
+}
+
+#[derive(Deserialize)]
+struct IndexResponse<T> {
+    links: PageLinks,
+    meta: PageMeta,
+    #[serde(flatten)]
+    data: HashMap<String, Vec<T>>,
+}
+
+impl<T> IndexResponse<T> {
+    /// Returns the page with the list on the json field <key>
+    fn into_page(mut self, key: &str) -> ApiResult<Page<T>> {
+        let data = self
+            .data
+            .remove(key)
+            .ok_or_else(|| ApiError::MissingEnvelope {
+                key: key.to_string(),
+            })?;
+
+        Ok(Page {
+            data,
+            links: self.links,
+            meta: self.meta,
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use crate::client::{AnimeThemesClient, IndexResponse};
+use crate::error::ApiResult;
+use crate::models::Page;
+use crate::pagination::{PaginateOptions, Pagination};
+use futures::future::{BoxFuture, Either};
+use futures::{Future, FutureExt, Stream, StreamExt};
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+impl AnimeThemesClient {
+    /// Walks through all pages of an index endpoint and yields the items of each page.
+    /// `fetch` requests the first page, every following page is requested from the
+    /// `links.next` url of the page before it until the api reports that there's no next page.
+    /// With a concurrency greater than one, the next pages are requested
+    /// while the items of previous pages are still being consumed.
+    /// The stream ends after the first error.
+    ///
+    /// ```
+    /// # use animethemes_rs::error::ApiResult;
+    /// use animethemes_rs::client::AnimeThemesClient;
+    /// use animethemes_rs::fields::Fieldsets;
+    /// use animethemes_rs::filters::VideoFilter;
+    /// use animethemes_rs::includes::VideoInclude;
+    /// use animethemes_rs::pagination::PaginateOptions;
+    /// use animethemes_rs::sort::Sort;
+    /// use futures::StreamExt;
+    ///
+    /// # async fn a() -> ApiResult<()> {
+    /// let client = AnimeThemesClient::default();
+    /// let options = PaginateOptions::default().page_size(100).concurrency(4);
+    /// let mut videos = Box::pin(client.paginate(options, |client, page| {
+    ///     client.list_videos(
+    ///         page,
+    ///         VideoInclude::default(),
+    ///         VideoFilter::default(),
+    ///         Sort::default(),
+    ///         Fieldsets::default(),
+    ///     )
+    /// }));
+    ///
+    /// while let Some(video) = videos.next().await {
+    ///     println!("{:?}", video?.basename);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn paginate<'a, T, F, Fut>(
+        &'a self,
+        options: PaginateOptions,
+        fetch: F,
+    ) -> impl Stream<Item = ApiResult<T>> + 'a
+    where
+        T: DeserializeOwned + Send + 'a,
+        F: FnOnce(&'a Self, Pagination) -> Fut + 'a,
+        Fut: Future<Output = ApiResult<Page<T>>> + 'a,
+    {
+        let first = fetch(
+            self,
+            Pagination {
+                size: options.page_size,
+                number: Some(1),
+            },
+        );
+
+        Paginated {
+            client: self,
+            fetching: Some(Either::Left(Box::pin(first))),
+            next: None,
+            pages: VecDeque::new(),
+            concurrency: options.concurrency.max(1),
+        }
+        .take(options.max_items.unwrap_or(usize::MAX))
+    }
+
+    /// Requests the page behind the `links` url of another page.
+    /// Urls of the api are requested like any other api request, others without the token.
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn page_by_url<T: DeserializeOwned>(&self, url: String) -> ApiResult<Page<T>> {
+        let api_url = url
+            .strip_prefix(&self.api_endpoint)
+            .zip(Url::parse(&url).ok());
+        let response: IndexResponse<T> = match api_url {
+            Some((path_and_query, parsed)) => {
+                let path = path_and_query.split('?').next().unwrap_or_default();
+                let query: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+                self.api_get_json(path, &query[..]).await?
+            }
+            None => self.get(&url, &()).await?.json().await?,
+        };
+        let key = response
+            .data
+            .keys()
+            .next()
+            .cloned()
+            .unwrap_or_else(|| "data".to_string());
+
+        response.into_page(&key)
+    }
+}
+
+type PageFuture<'a, T, Fut> = Either<Pin<Box<Fut>>, BoxFuture<'a, ApiResult<Page<T>>>>;
+
+/// Yields the items of a page while following its next link
+struct Paginated<'a, T, Fut> {
+    client: &'a AnimeThemesClient,
+    fetching: Option<PageFuture<'a, T, Fut>>,
+    next: Option<String>,
+    pages: VecDeque<std::vec::IntoIter<ApiResult<T>>>,
+    concurrency: usize,
+}
+
+// the futures are boxed and the items are never pinned
+impl<T, Fut> Unpin for Paginated<'_, T, Fut> {}
+
+impl<'a, T, Fut> Stream for Paginated<'a, T, Fut>
+where
+    T: DeserializeOwned + Send + 'a,
+    Fut: Future<Output = ApiResult<Page<T>>> + 'a,
+{
+    type Item = ApiResult<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(Poll::Ready(result)) = this.fetching.as_mut().map(|f| f.poll_unpin(cx)) {
+                this.fetching = None;
+                let items = match result {
+                    Ok(page) if page.data.is_empty() => Vec::new(),
+                    Ok(page) => {
+                        this.next = page.links.next;
+                        page.data.into_iter().map(Ok).collect()
+                    }
+                    Err(e) => vec![Err(e)],
+                };
+                this.pages.push_back(items.into_iter());
+            }
+            if this.fetching.is_none() && this.pages.len() < this.concurrency {
+                if let Some(url) = this.next.take() {
+                    let page = this.client.page_by_url(url).boxed();
+                    this.fetching = Some(Either::Right(page));
+                    continue;
+                }
+            }
+
+            while let Some(page) = this.pages.front_mut() {
+                match page.next() {
+                    Some(item) => return Poll::Ready(Some(item)),
+                    None => {
+                        this.pages.pop_front();
+                    }
+                }
+            }
+
+            match (&this.fetching, &this.next) {
+                (None, None) => return Poll::Ready(None),
+                (Some(_), _) => return Poll::Pending,
+                (None, Some(_)) => {}
+            }
+        }
+    }
+}
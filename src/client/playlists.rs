@@ -0,0 +1,186 @@
+use crate::client::{envelope, AnimeThemesClient};
+use crate::error::ApiResult;
+use crate::fields::Fieldsets;
+use crate::filters::*;
+use crate::includes::*;
+use crate::models::{
+    NewPlaylist, NewPlaylistTrack, Page, Playlist, PlaylistTrack, PlaylistTrackUpdate,
+    PlaylistUpdate,
+};
+use crate::pagination::{PaginateOptions, Pagination};
+use crate::sort::*;
+use futures::TryStreamExt;
+
+impl AnimeThemesClient {
+    /// Returns a playlist by its hashid
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn playlist(
+        &self,
+        id: &str,
+        include: PlaylistInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Playlist> {
+        self.entry_by_id_with_include("playlist", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a page of public playlists
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_playlists(
+        &self,
+        pagination: Pagination,
+        include: PlaylistInclude,
+        filter: PlaylistFilter,
+        sort: Sort<PlaylistSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Playlist>> {
+        self.index_with_include(
+            "playlist",
+            "playlists",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a track of a playlist by the hashids of the playlist and track
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn playlist_track(
+        &self,
+        playlist_id: &str,
+        track_id: &str,
+        include: PlaylistTrackInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<PlaylistTrack> {
+        self.show_with_include(
+            format!("/playlist/{}/track/{}", playlist_id, track_id).as_str(),
+            "track",
+            include.includes(),
+            fields.query(),
+        )
+        .await
+    }
+
+    /// Returns a page of the tracks of a playlist
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_playlist_tracks(
+        &self,
+        playlist_id: &str,
+        pagination: Pagination,
+        include: PlaylistTrackInclude,
+        filter: PlaylistTrackFilter,
+        sort: Sort<PlaylistTrackSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<PlaylistTrack>> {
+        self.index_with_include(
+            format!("playlist/{}/track", playlist_id).as_str(),
+            "tracks",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns all tracks of a playlist in playback order
+    /// by following the `previous` and `next` links of the tracks
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn playlist_tracks_in_order(
+        &self,
+        playlist_id: &str,
+        include: PlaylistTrackInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<Vec<PlaylistTrack>> {
+        let include = include.previous().next();
+        let tracks = self
+            .paginate(PaginateOptions::default(), |client, page| {
+                client.list_playlist_tracks(
+                    playlist_id,
+                    page,
+                    include,
+                    PlaylistTrackFilter::default(),
+                    Sort::default(),
+                    fields,
+                )
+            })
+            .try_collect()
+            .await?;
+
+        Ok(PlaylistTrack::playback_order(tracks))
+    }
+
+    /// Creates a new playlist owned by the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn create_playlist(&self, playlist: &NewPlaylist) -> ApiResult<Playlist> {
+        let response = self.api_post("/playlist", playlist).await?;
+        self.invalidate_playlists();
+
+        envelope(response, "playlist").await
+    }
+
+    /// Updates a playlist of the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_playlist(&self, id: &str, update: &PlaylistUpdate) -> ApiResult<Playlist> {
+        let response = self
+            .api_put(format!("/playlist/{}", id).as_str(), update)
+            .await?;
+        self.invalidate_playlists();
+
+        envelope(response, "playlist").await
+    }
+
+    /// Deletes a playlist of the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_playlist(&self, id: &str) -> ApiResult<()> {
+        self.api_delete(format!("/playlist/{}", id).as_str())
+            .await?;
+        self.invalidate_playlists();
+
+        Ok(())
+    }
+
+    /// Adds a track to a playlist of the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn create_playlist_track(
+        &self,
+        playlist_id: &str,
+        track: &NewPlaylistTrack,
+    ) -> ApiResult<PlaylistTrack> {
+        let response = self
+            .api_post(format!("/playlist/{}/track", playlist_id).as_str(), track)
+            .await?;
+        self.invalidate_playlists();
+
+        envelope(response, "track").await
+    }
+
+    /// Updates a track of a playlist of the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn update_playlist_track(
+        &self,
+        playlist_id: &str,
+        track_id: &str,
+        update: &PlaylistTrackUpdate,
+    ) -> ApiResult<PlaylistTrack> {
+        let response = self
+            .api_put(
+                format!("/playlist/{}/track/{}", playlist_id, track_id).as_str(),
+                update,
+            )
+            .await?;
+        self.invalidate_playlists();
+
+        envelope(response, "track").await
+    }
+
+    /// Removes a track from a playlist of the authenticated user
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn delete_playlist_track(&self, playlist_id: &str, track_id: &str) -> ApiResult<()> {
+        self.api_delete(format!("/playlist/{}/track/{}", playlist_id, track_id).as_str())
+            .await?;
+        self.invalidate_playlists();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::client::AnimeThemesClient;
+use crate::error::ApiResult;
+use crate::fields::Fieldsets;
+use crate::filters::*;
+use crate::includes::*;
+use crate::models::{AnimeYear, Announcement, Dump, FeaturedTheme, Page};
+use crate::pagination::Pagination;
+use crate::sort::*;
+
+impl AnimeThemesClient {
+    /// Returns an announcement by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn announcement(&self, id: u32, fields: Fieldsets) -> ApiResult<Announcement> {
+        self.entry_by_id_with_include("announcement", id, Vec::new(), fields.query())
+            .await
+    }
+
+    /// Returns a page of announcements
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_announcements(
+        &self,
+        pagination: Pagination,
+        filter: AnnouncementFilter,
+        sort: Sort<AnnouncementSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Announcement>> {
+        self.index_with_include(
+            "announcement",
+            "announcements",
+            pagination,
+            Vec::new(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a database dump by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn dump(&self, id: u32, fields: Fieldsets) -> ApiResult<Dump> {
+        self.entry_by_id_with_include("dump", id, Vec::new(), fields.query())
+            .await
+    }
+
+    /// Returns a page of database dumps
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_dumps(
+        &self,
+        pagination: Pagination,
+        filter: DumpFilter,
+        sort: Sort<DumpSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<Dump>> {
+        self.index_with_include(
+            "dump",
+            "dumps",
+            pagination,
+            Vec::new(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns a featured theme by id
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn featured_theme(
+        &self,
+        id: u32,
+        include: FeaturedThemeInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<FeaturedTheme> {
+        self.entry_by_id_with_include("featuredtheme", id, include.includes(), fields.query())
+            .await
+    }
+
+    /// Returns a page of featured themes
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn list_featured_themes(
+        &self,
+        pagination: Pagination,
+        include: FeaturedThemeInclude,
+        filter: FeaturedThemeFilter,
+        sort: Sort<FeaturedThemeSort>,
+        fields: Fieldsets,
+    ) -> ApiResult<Page<FeaturedTheme>> {
+        self.index_with_include(
+            "featuredtheme",
+            "featuredthemes",
+            pagination,
+            include.includes(),
+            [filter.query(false), sort.query(false), fields.query()].concat(),
+        )
+        .await
+    }
+
+    /// Returns the theme that is currently featured on the homepage
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn current_featured_theme(
+        &self,
+        include: FeaturedThemeInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<FeaturedTheme> {
+        self.show_with_include(
+            "/current/featuredtheme",
+            "featuredtheme",
+            include.includes(),
+            fields.query(),
+        )
+        .await
+    }
+
+    /// Returns all years that have anime
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn anime_years(&self) -> ApiResult<Vec<u16>> {
+        self.api_get_json("/animeyear", &[]).await
+    }
+
+    /// Returns the anime of a year grouped by season
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn anime_year(
+        &self,
+        year: u16,
+        include: AnimeInclude,
+        fields: Fieldsets,
+    ) -> ApiResult<AnimeYear> {
+        let mut query = fields.query();
+        let include = include.includes();
+
+        if !include.is_empty() {
+            query.push(("include".to_string(), include.join(",")));
+        }
+        self.api_get_json(format!("/animeyear/{}", year).as_str(), &query[..])
+            .await
+    }
+}
@@ -0,0 +1,295 @@
+//! Offline access to the database dumps published by AnimeThemes.
+//!
+//! The dumps are plain mysql dumps that can be downloaded with
+//! [AnimeThemesClient::download_latest_dump](crate::client::AnimeThemesClient::download_latest_dump)
+//! and imported with [DumpData::from_sql].
+//!
+//! ```no_run
+//! # use animethemes_rs::error::ApiResult;
+//! use animethemes_rs::client::AnimeThemesClient;
+//! use animethemes_rs::dump::DumpData;
+//! use animethemes_rs::filters::{Comparison, DumpFilter};
+//!
+//! # async fn a() -> ApiResult<()> {
+//! let client = AnimeThemesClient::default();
+//! let mut sql = Vec::new();
+//! client
+//!     .download_latest_dump(
+//!         DumpFilter::default().path_cmp(Comparison::Like, "%wiki%"),
+//!         &mut sql,
+//!         |progress| println!("{} bytes downloaded", progress.downloaded),
+//!     )
+//!     .await?;
+//! let data = DumpData::from_sql(&sql[..], &client.video_endpoint)?;
+//! println!("{} anime", data.anime.len());
+//! # Ok(()) }
+//! ```
+
+use crate::error::ApiResult;
+use crate::models::{
+    Anime, AnimeSeason, Artist, EntryMetadata, Song, Theme, ThemeEntry, ThemeType, Video,
+    VideoOverlap, VideoSource,
+};
+use std::io::{BufRead, BufReader, Read};
+
+mod sql;
+
+use sql::SqlRow;
+
+/// The progress of a running download
+#[derive(Clone, Copy, Debug)]
+pub struct DownloadProgress {
+    /// The number of bytes written so far
+    pub downloaded: u64,
+    /// The size of the download if it is known
+    pub total: Option<u64>,
+}
+
+/// The entries imported from a database dump.
+/// Relations are not nested into the entries but returned as id pairs in [DumpRelations].
+#[derive(Clone, Debug, Default)]
+pub struct DumpData {
+    pub anime: Vec<Anime>,
+    pub themes: Vec<Theme>,
+    pub entries: Vec<ThemeEntry>,
+    pub videos: Vec<Video>,
+    pub songs: Vec<Song>,
+    pub artists: Vec<Artist>,
+    pub relations: DumpRelations,
+}
+
+/// The relations between the entries of a dump as pairs of ids
+#[derive(Clone, Debug, Default)]
+pub struct DumpRelations {
+    /// (theme_id, anime_id)
+    pub theme_anime: Vec<(u32, u32)>,
+    /// (theme_id, song_id)
+    pub theme_song: Vec<(u32, u32)>,
+    /// (entry_id, theme_id)
+    pub entry_theme: Vec<(u32, u32)>,
+    /// (entry_id, video_id)
+    pub entry_video: Vec<(u32, u32)>,
+    /// (artist_id, song_id)
+    pub artist_song: Vec<(u32, u32)>,
+    /// (artist_id, song_id, character) for artists performing a song as a character
+    pub song_credits: Vec<(u32, u32, String)>,
+}
+
+impl DumpData {
+    /// Imports the entries from the sql of a database dump while reading it.
+    /// The links of the videos point to the given video endpoint like
+    /// [DEFAULT_VIDEO_ENDPOINT](crate::client::DEFAULT_VIDEO_ENDPOINT).
+    pub fn from_sql<R: BufRead>(sql: R, video_endpoint: &str) -> ApiResult<Self> {
+        let mut data = Self::default();
+        sql::read_rows(sql, |table, row| data.insert(table, &row, video_endpoint))?;
+
+        Ok(data)
+    }
+
+    /// Reads the sql of a database dump and imports the entries
+    pub fn from_reader<R: Read>(reader: R, video_endpoint: &str) -> ApiResult<Self> {
+        Self::from_sql(BufReader::new(reader), video_endpoint)
+    }
+
+    /// Imports a row of a table
+    fn insert(&mut self, table: &str, row: &SqlRow, video_endpoint: &str) {
+        match table {
+            "anime" => self.anime.push(anime(row)),
+            "anime_themes" => {
+                let theme = theme(row);
+
+                if let (Some(theme_id), Some(anime_id)) = (theme.meta.id, row.number("anime_id")) {
+                    self.relations.theme_anime.push((theme_id, anime_id));
+                }
+                if let (Some(theme_id), Some(song_id)) = (theme.meta.id, row.number("song_id")) {
+                    self.relations.theme_song.push((theme_id, song_id));
+                }
+                self.themes.push(theme);
+            }
+            "anime_theme_entries" => {
+                let entry = entry(row);
+
+                if let (Some(entry_id), Some(theme_id)) = (entry.meta.id, row.number("theme_id")) {
+                    self.relations.entry_theme.push((entry_id, theme_id));
+                }
+                self.entries.push(entry);
+            }
+            "videos" => self.videos.push(video(row, video_endpoint)),
+            "songs" => self.songs.push(song(row)),
+            "artists" => self.artists.push(artist(row)),
+            "anime_theme_entry_video" => {
+                self.relations
+                    .entry_video
+                    .extend(pivot(row, "entry_id", "video_id"));
+            }
+            "artist_song" => {
+                let Some((artist_id, song_id)) = pivot(row, "artist_id", "song_id") else {
+                    return;
+                };
+                self.relations.artist_song.push((artist_id, song_id));
+
+                if let Some(character) = row.string("as") {
+                    self.relations
+                        .song_credits
+                        .push((artist_id, song_id, character));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn pivot(row: &SqlRow, left: &str, right: &str) -> Option<(u32, u32)> {
+    Some((row.number(left)?, row.number(right)?))
+}
+
+fn meta(row: &SqlRow, id_column: &str) -> EntryMetadata {
+    EntryMetadata {
+        id: row.number(id_column),
+        created_at: row.string("created_at"),
+        updated_at: row.string("updated_at"),
+        deleted_at: row.string("deleted_at"),
+    }
+}
+
+/// Enums are stored by their numeric value. Older dumps store the name instead.
+fn enum_value<T>(row: &SqlRow, column: &str, variants: &[(&str, T)]) -> Option<T>
+where
+    T: Clone,
+{
+    let value = row.str(column)?;
+
+    match value.parse::<usize>() {
+        Ok(index) => variants.get(index).map(|(_, v)| v.clone()),
+        Err(_) => variants
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(value))
+            .map(|(_, v)| v.clone()),
+    }
+}
+
+fn anime(row: &SqlRow) -> Anime {
+    Anime {
+        meta: meta(row, "anime_id"),
+        name: row.string("name"),
+        slug: row.string("slug"),
+        year: row.number("year"),
+        season: enum_value(
+            row,
+            "season",
+            &[
+                ("winter", AnimeSeason::Winter),
+                ("spring", AnimeSeason::Spring),
+                ("summer", AnimeSeason::Summer),
+                ("fall", AnimeSeason::Fall),
+            ],
+        ),
+        synopsis: row.string("synopsis"),
+        ..Default::default()
+    }
+}
+
+fn theme(row: &SqlRow) -> Theme {
+    let theme_type = enum_value(
+        row,
+        "type",
+        &[
+            ("op", Some(ThemeType::OP)),
+            ("ed", Some(ThemeType::ED)),
+            ("in", None),
+        ],
+    )
+    .flatten();
+    let sequence = row.number("sequence");
+    // the slug is only stored by older dumps and derived from the type and sequence otherwise
+    let slug = row.string("slug").or_else(|| {
+        let prefix = theme_type
+            .as_ref()
+            .map(|t| format!("{t:?}"))
+            .unwrap_or_default();
+        let sequence = sequence.map(|s: u16| s.to_string()).unwrap_or_default();
+
+        Some(format!("{prefix}{sequence}"))
+    });
+
+    Theme {
+        meta: meta(row, "theme_id"),
+        theme_type,
+        sequence,
+        group: row.string("group"),
+        slug,
+        ..Default::default()
+    }
+}
+
+fn entry(row: &SqlRow) -> ThemeEntry {
+    ThemeEntry {
+        meta: meta(row, "entry_id"),
+        version: row.number("version"),
+        episodes: row.string("episodes"),
+        nsfw: row.bool("nsfw"),
+        spoiler: row.bool("spoiler"),
+        notes: row.string("notes"),
+        ..Default::default()
+    }
+}
+
+fn video(row: &SqlRow, video_endpoint: &str) -> Video {
+    let basename = row.string("basename");
+
+    Video {
+        meta: meta(row, "video_id"),
+        link: basename
+            .as_ref()
+            .map(|basename| format!("{video_endpoint}{basename}")),
+        basename,
+        filename: row.string("filename"),
+        path: row.string("path"),
+        resolution: row.number("resolution"),
+        no_credits: row.bool("nc"),
+        subbed: row.bool("subbed"),
+        lyrics: row.bool("lyrics"),
+        uncensored: row.bool("uncen"),
+        source: enum_value(
+            row,
+            "source",
+            &[
+                ("web", Some(VideoSource::WEB)),
+                ("raw", Some(VideoSource::RAW)),
+                ("bd", Some(VideoSource::BD)),
+                ("dvd", Some(VideoSource::DVD)),
+                ("vhs", Some(VideoSource::VHS)),
+                ("ld", None),
+            ],
+        )
+        .flatten(),
+        overlap: enum_value(
+            row,
+            "overlap",
+            &[
+                ("none", VideoOverlap::None),
+                ("transition", VideoOverlap::Transition),
+                ("over", VideoOverlap::Over),
+            ],
+        ),
+        tags: row.string("tags"),
+        ..Default::default()
+    }
+}
+
+fn song(row: &SqlRow) -> Song {
+    Song {
+        meta: meta(row, "song_id"),
+        title: row.string("title"),
+        ..Default::default()
+    }
+}
+
+fn artist(row: &SqlRow) -> Artist {
+    Artist {
+        meta: meta(row, "artist_id"),
+        name: row.string("name"),
+        slug: row.string("slug"),
+        ..Default::default()
+    }
+}
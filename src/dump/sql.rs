@@ -0,0 +1,407 @@
+//! A minimal parser for the mysql dumps published by AnimeThemes.
+//! Only `CREATE TABLE` statements (for the column names) and `INSERT` statements
+//! are evaluated, everything else is skipped.
+
+use crate::error::{ApiError, ApiResult};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Number(String),
+    String(String),
+}
+
+impl SqlValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SqlValue::Null => None,
+            SqlValue::Number(n) => Some(n),
+            SqlValue::String(s) => Some(s),
+        }
+    }
+}
+
+/// The values of a row with access by column name
+pub struct SqlRow<'a> {
+    positions: &'a HashMap<String, usize>,
+    values: &'a [SqlValue],
+}
+
+impl<'a> SqlRow<'a> {
+    pub fn get(&self, column: &str) -> Option<&'a SqlValue> {
+        self.values.get(*self.positions.get(column)?)
+    }
+
+    pub fn str(&self, column: &str) -> Option<&'a str> {
+        self.get(column)?.as_str()
+    }
+
+    pub fn string(&self, column: &str) -> Option<String> {
+        self.str(column).map(String::from)
+    }
+
+    pub fn number<T: std::str::FromStr>(&self, column: &str) -> Option<T> {
+        self.str(column)?.parse().ok()
+    }
+
+    pub fn bool(&self, column: &str) -> Option<bool> {
+        self.str(column).map(|value| matches!(value, "1" | "true"))
+    }
+}
+
+/// Reads the statements of a sql dump one after another
+/// and passes every inserted row to the callback with the name of its table
+pub fn read_rows<R, F>(reader: R, mut on_row: F) -> ApiResult<()>
+where
+    R: BufRead,
+    F: FnMut(&str, SqlRow<'_>),
+{
+    let mut tables: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+    for statement in Statements::new(reader) {
+        let tokens = tokenize(&statement?)?;
+
+        match tokens.first() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("create") => {
+                if let Some((name, columns)) = parse_create_table(&tokens) {
+                    tables.insert(name, positions(columns));
+                }
+            }
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("insert") => {
+                let (name, columns, rows) = parse_insert(&tokens)?;
+                let columns = columns.map(positions);
+                let positions = match columns.as_ref().or_else(|| tables.get(&name)) {
+                    Some(positions) if !positions.is_empty() => positions,
+                    _ => {
+                        return Err(ApiError::Dump(format!(
+                            "missing column definitions for table {name}"
+                        )))
+                    }
+                };
+
+                for values in &rows {
+                    on_row(&name, SqlRow { positions, values });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn positions(columns: Vec<String>) -> HashMap<String, usize> {
+    columns
+        .into_iter()
+        .enumerate()
+        .map(|(i, column)| (column, i))
+        .collect()
+}
+
+/// Splits a dump into statements while reading it line by line, dropping comments
+struct Statements<R> {
+    reader: R,
+    line: String,
+    statement: String,
+    quote: Option<char>,
+    block_comment: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Statements<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            statement: String::new(),
+            quote: None,
+            block_comment: false,
+            done: false,
+        }
+    }
+
+    /// Adds the line to the current statement and returns if the statement is complete.
+    /// The rest of a line after the end of a statement is kept in `line`.
+    fn push_line(&mut self) -> bool {
+        let mut chars = self.line.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            if self.block_comment {
+                if c == '*' && matches!(chars.peek(), Some((_, '/'))) {
+                    chars.next();
+                    self.block_comment = false;
+                    self.statement.push(' ');
+                }
+                continue;
+            }
+            if let Some(quote) = self.quote {
+                self.statement.push(c);
+                if c == '\\' && quote != '`' {
+                    self.statement.extend(chars.next().map(|(_, c)| c));
+                } else if c == quote {
+                    self.quote = None;
+                }
+                continue;
+            }
+            match c {
+                '\'' | '"' | '`' => {
+                    self.quote = Some(c);
+                    self.statement.push(c);
+                }
+                '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                    self.statement.push('\n');
+                    break;
+                }
+                '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                    chars.next();
+                    self.block_comment = true;
+                }
+                ';' => {
+                    self.line.drain(..=i);
+                    return true;
+                }
+                c => self.statement.push(c),
+            }
+        }
+        self.line.clear();
+
+        false
+    }
+
+    fn take_statement(&mut self) -> Option<String> {
+        let statement = self.statement.trim().to_string();
+        self.statement.clear();
+
+        (!statement.is_empty()).then_some(statement)
+    }
+}
+
+impl<R: BufRead> Iterator for Statements<R> {
+    type Item = ApiResult<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if self.line.is_empty() {
+                match self.reader.read_line(&mut self.line) {
+                    Ok(0) => {
+                        self.done = true;
+                        return self.take_statement().map(Ok);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        self.done = true;
+                        return Some(Err(e.into()));
+                    }
+                }
+            }
+            if self.push_line() {
+                if let Some(statement) = self.take_statement() {
+                    return Some(Ok(statement));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Identifier(String),
+    Value(SqlValue),
+    Punct(char),
+}
+
+fn tokenize(statement: &str) -> ApiResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = statement.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '\'' | '"' => {
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('r') => value.push('\r'),
+                            Some('t') => value.push('\t'),
+                            Some('0') => value.push('\0'),
+                            Some('Z') => value.push('\x1a'),
+                            Some(escaped) => value.push(escaped),
+                            None => break,
+                        },
+                        Some(q) if q == c => {
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                                value.push(c);
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(other) => value.push(other),
+                        None => {
+                            return Err(ApiError::Dump("unterminated string".to_string()));
+                        }
+                    }
+                }
+                tokens.push(Token::Value(SqlValue::String(value)));
+            }
+            '`' => {
+                let identifier: String = chars.by_ref().take_while(|c| *c != '`').collect();
+                tokens.push(Token::Identifier(identifier));
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && matches!(chars.peek(), Some(d) if d.is_ascii_digit())) =>
+            {
+                let mut number = c.to_string();
+
+                while let Some(d) = chars.peek() {
+                    if d.is_ascii_alphanumeric() || *d == '.' || *d == '-' || *d == '+' {
+                        number.push(*d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Value(SqlValue::Number(number)));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+
+                while let Some(d) = chars.peek() {
+                    if d.is_alphanumeric() || *d == '_' {
+                        word.push(*d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if word.eq_ignore_ascii_case("null") {
+                    tokens.push(Token::Value(SqlValue::Null));
+                } else {
+                    tokens.push(Token::Word(word));
+                }
+            }
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_create_table(tokens: &[Token]) -> Option<(String, Vec<String>)> {
+    let mut iter = tokens.iter();
+    iter.find(|t| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case("table")))?;
+    let name = iter.find_map(|t| match t {
+        Token::Identifier(name) => Some(name.clone()),
+        Token::Word(w) if !is_keyword(w) => Some(w.clone()),
+        _ => None,
+    })?;
+    iter.find(|t| **t == Token::Punct('('))?;
+
+    let mut columns = Vec::new();
+    let mut depth = 1;
+    let mut definition_start = true;
+
+    for token in iter {
+        match token {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            Token::Punct(',') if depth == 1 => {
+                definition_start = true;
+                continue;
+            }
+            Token::Identifier(column) if definition_start => columns.push(column.clone()),
+            Token::Word(column) if definition_start && !is_keyword(column) => {
+                columns.push(column.clone())
+            }
+            _ => {}
+        }
+        definition_start = false;
+    }
+
+    Some((name, columns))
+}
+
+type InsertStatement = (String, Option<Vec<String>>, Vec<Vec<SqlValue>>);
+
+fn parse_insert(tokens: &[Token]) -> ApiResult<InsertStatement> {
+    let invalid = || ApiError::Dump("invalid insert statement".to_string());
+    let mut iter = tokens.iter().peekable();
+    iter.find(|t| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case("into")))
+        .ok_or_else(invalid)?;
+    let name = match iter.next() {
+        Some(Token::Identifier(name)) | Some(Token::Word(name)) => name.clone(),
+        _ => return Err(invalid()),
+    };
+    let mut columns = None;
+
+    if iter.peek() == Some(&&Token::Punct('(')) {
+        iter.next();
+        let mut names = Vec::new();
+
+        for token in iter.by_ref() {
+            match token {
+                Token::Identifier(column) | Token::Word(column) => names.push(column.clone()),
+                Token::Punct(')') => break,
+                _ => {}
+            }
+        }
+        columns = Some(names);
+    }
+    iter.find(|t| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case("values")))
+        .ok_or_else(invalid)?;
+
+    let mut rows = Vec::new();
+    let mut row: Option<Vec<SqlValue>> = None;
+
+    for token in iter {
+        match (token, row.as_mut()) {
+            (Token::Punct('('), None) => row = Some(Vec::new()),
+            (Token::Punct(')'), Some(_)) => rows.extend(row.take()),
+            (Token::Value(value), Some(values)) => values.push(value.clone()),
+            (Token::Word(word), Some(values)) if word.eq_ignore_ascii_case("true") => {
+                values.push(SqlValue::Number("1".to_string()))
+            }
+            (Token::Word(word), Some(values)) if word.eq_ignore_ascii_case("false") => {
+                values.push(SqlValue::Number("0".to_string()))
+            }
+            // charset introducers like _binary'...' are followed by the actual value
+            (Token::Word(word), Some(_)) if word.starts_with('_') => {}
+            (Token::Word(word), Some(values)) => values.push(SqlValue::String(word.clone())),
+            (Token::Word(word), None) if word.eq_ignore_ascii_case("on") => break,
+            _ => {}
+        }
+    }
+
+    Ok((name, columns, rows))
+}
+
+fn is_keyword(word: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "primary",
+        "key",
+        "unique",
+        "index",
+        "constraint",
+        "foreign",
+        "fulltext",
+        "spatial",
+        "check",
+        "if",
+        "not",
+        "exists",
+    ];
+    KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k))
+}
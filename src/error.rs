@@ -1,3 +1,9 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 pub type ApiResult<T> = Result<T, ApiError>;
@@ -6,4 +12,177 @@ pub type ApiResult<T> = Result<T, ApiError>;
 pub enum ApiError {
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
+
+    #[error("the requested resource does not exist")]
+    NotFound,
+
+    #[error("not authorized to perform the request ({status}): {}", format_errors(.errors))]
+    Unauthorized {
+        status: StatusCode,
+        errors: Vec<JsonApiError>,
+    },
+
+    #[error("rate limited by the api (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("the api responded with status {status}: {}", format_errors(.errors))]
+    Server {
+        status: StatusCode,
+        errors: Vec<JsonApiError>,
+    },
+
+    #[error("the response is missing the '{key}' field")]
+    MissingEnvelope { key: String },
+
+    #[error(
+        "the entry is missing the '{name}' attribute, it might be excluded by the requested fields"
+    )]
+    MissingAttribute { name: String },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to import the database dump: {0}")]
+    Dump(String),
+
+    #[error("no fixture recorded for {0}")]
+    MissingFixture(String),
+}
+
+impl ApiError {
+    /// Returns the response status of the error if the api responded
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Reqwest(e) => e.status(),
+            Self::NotFound => Some(StatusCode::NOT_FOUND),
+            Self::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Self::Unauthorized { status, .. } | Self::Server { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Returns how long to wait before retrying if the api returned a `Retry-After` header
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Converts an unsuccessful response into the matching error variant
+    pub(crate) async fn from_response(response: Response) -> Self {
+        let status = response.status();
+
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                retry_after: retry_after(&response),
+            },
+            _ => {
+                let errors = response
+                    .json::<ErrorBody>()
+                    .await
+                    .map(ErrorBody::into_errors)
+                    .unwrap_or_default();
+
+                if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+                    Self::Unauthorized { status, errors }
+                } else {
+                    Self::Server { status, errors }
+                }
+            }
+        }
+    }
+}
+
+/// A single entry of the `errors` array returned by the api
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JsonApiError {
+    pub status: Option<String>,
+    pub title: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl Display for JsonApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.title, &self.detail) {
+            (Some(title), Some(detail)) => write!(f, "{title} ({detail})"),
+            (Some(message), None) | (None, Some(message)) => write!(f, "{message}"),
+            (None, None) => write!(f, "unknown error"),
+        }
+    }
+}
+
+/// The error body returned by the api. Next to the json:api `errors` array
+/// the api can also return laravel style validation errors grouped by field.
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: Option<String>,
+    errors: Option<ErrorList>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorList {
+    JsonApi(Vec<JsonApiError>),
+    Validation(HashMap<String, Vec<String>>),
+}
+
+impl ErrorBody {
+    fn into_errors(self) -> Vec<JsonApiError> {
+        match self.errors {
+            Some(ErrorList::JsonApi(errors)) if !errors.is_empty() => errors,
+            Some(ErrorList::Validation(fields)) if !fields.is_empty() => fields
+                .into_iter()
+                .flat_map(|(field, messages)| {
+                    messages.into_iter().map(move |message| JsonApiError {
+                        status: None,
+                        title: Some(field.clone()),
+                        detail: Some(message),
+                    })
+                })
+                .collect(),
+            _ => self
+                .message
+                .map(|message| JsonApiError {
+                    title: Some(message),
+                    ..Default::default()
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Reads the `Retry-After` header of a response in seconds
+fn retry_after(response: &Response) -> Option<Duration> {
+    parse_retry_after(response.headers())
+}
+
+/// Parses the `Retry-After` header given either in seconds or as http date
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = httpdate::parse_http_date(value).ok()?;
+
+            Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+        }
+    }
+}
+
+fn format_errors(errors: &[JsonApiError]) -> String {
+    if errors.is_empty() {
+        return "no error details".to_string();
+    }
+    errors
+        .iter()
+        .map(JsonApiError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
@@ -0,0 +1,359 @@
+macro_rules! theme_fields {
+    (
+        pub struct $name: ident ($field_type:literal) {
+            $($field: ident: $field_name: literal),+
+        }
+    ) => {
+        #[derive(Clone, Copy, Default, Debug)]
+        pub struct $name {
+            $($field: bool),+
+        }
+
+        impl $name {
+            $(pub fn $field(mut self) -> Self {
+                self.$field = true;
+
+                self
+            }
+            )+
+        }
+
+        impl Fields for $name {
+            fn field_type() -> &'static str {
+                $field_type
+            }
+
+            fn fields(&self) -> Vec<String> {
+                let mut fields = Vec::new();
+                $(
+                    if self.$field {
+                        fields.push($field_name.into());
+                    }
+                )+
+
+                fields
+            }
+        }
+    }
+}
+
+pub trait Fields {
+    fn field_type() -> &'static str;
+    fn fields(&self) -> Vec<String>;
+
+    /// Returns the `fields[<type>]` query parameter or nothing if all fields should be returned
+    fn query(&self) -> Vec<(String, String)> {
+        let fields = self.fields();
+
+        if fields.is_empty() {
+            Vec::new()
+        } else {
+            vec![(format!("fields[{}]", Self::field_type()), fields.join(","))]
+        }
+    }
+}
+
+theme_fields!(
+    pub struct SearchResultFields ("search") {
+        anime: "anime",
+        themes: "animethemes",
+        artists: "artists",
+        series: "series",
+        songs: "songs",
+        videos: "videos"
+    }
+);
+
+theme_fields!(
+    pub struct AnnouncementFields ("announcement") {
+        id: "id",
+        content: "content",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct DumpFields ("dump") {
+        id: "id",
+        path: "path",
+        link: "link",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct FeaturedThemeFields ("featuredtheme") {
+        id: "id",
+        start_at: "start_at",
+        end_at: "end_at",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct AnimeFields ("anime") {
+        id: "id",
+        name: "name",
+        slug: "slug",
+        year: "year",
+        season: "season",
+        synopsis: "synopsis",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct SynonymFields ("animesynonym") {
+        id: "id",
+        text: "text",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct AudioFields ("audio") {
+        id: "id",
+        basename: "basename",
+        filename: "filename",
+        path: "path",
+        size: "size",
+        mimetype: "mimetype",
+        link: "link",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct ThemeFields ("animetheme") {
+        id: "id",
+        theme_type: "type",
+        sequence: "sequence",
+        group: "group",
+        slug: "slug",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct ThemeEntryFields ("animethemeentry") {
+        id: "id",
+        version: "version",
+        episodes: "episodes",
+        nsfw: "nsfw",
+        spoiler: "spoiler",
+        notes: "notes",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct ArtistFields ("artist") {
+        id: "id",
+        name: "name",
+        slug: "slug",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct ImageFields ("image") {
+        id: "id",
+        path: "path",
+        facet: "facet",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct PlaylistFields ("playlist") {
+        id: "id",
+        name: "name",
+        visibility: "visibility",
+        description: "description",
+        tracks_count: "tracks_count",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct PlaylistTrackFields ("playlisttrack") {
+        id: "id",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct ResourceFields ("resource") {
+        id: "id",
+        link: "link",
+        external_id: "external_id",
+        site: "site",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct SeriesFields ("series") {
+        id: "id",
+        name: "name",
+        slug: "slug",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct SongFields ("song") {
+        id: "id",
+        title: "title",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct StudioFields ("studio") {
+        id: "id",
+        name: "name",
+        slug: "slug",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct UserFields ("user") {
+        id: "id",
+        name: "name",
+        email: "email",
+        email_verified_at: "email_verified_at",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct VideoFields ("video") {
+        id: "id",
+        basename: "basename",
+        filename: "filename",
+        path: "path",
+        resolution: "resolution",
+        no_credits: "nc",
+        subbed: "subbed",
+        lyrics: "lyrics",
+        uncensored: "uncen",
+        source: "source",
+        overlap: "overlap",
+        tags: "tags",
+        link: "link",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+theme_fields!(
+    pub struct VideoScriptFields ("videoscript") {
+        id: "id",
+        path: "path",
+        link: "link",
+        created_at: "created_at",
+        updated_at: "updated_at",
+        deleted_at: "deleted_at"
+    }
+);
+
+/// The sparse fieldsets of a request. Each type only returns the selected fields
+/// in the response, including related entries of that type. If no fields are selected
+/// for a type, all of them are returned.
+///
+/// ```
+/// use animethemes_rs::fields::{Fieldsets, VideoFields};
+///
+/// let fields = Fieldsets {
+///     videos: VideoFields::default().basename().link().resolution(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Fieldsets {
+    pub search: SearchResultFields,
+    pub announcements: AnnouncementFields,
+    pub dumps: DumpFields,
+    pub featured_themes: FeaturedThemeFields,
+    pub anime: AnimeFields,
+    pub synonyms: SynonymFields,
+    pub audio: AudioFields,
+    pub themes: ThemeFields,
+    pub entries: ThemeEntryFields,
+    pub artists: ArtistFields,
+    pub images: ImageFields,
+    pub playlists: PlaylistFields,
+    pub playlist_tracks: PlaylistTrackFields,
+    pub resources: ResourceFields,
+    pub series: SeriesFields,
+    pub songs: SongFields,
+    pub studios: StudioFields,
+    pub users: UserFields,
+    pub videos: VideoFields,
+    pub video_scripts: VideoScriptFields,
+}
+
+impl Fieldsets {
+    pub fn query(&self) -> Vec<(String, String)> {
+        [
+            self.search.query(),
+            self.announcements.query(),
+            self.dumps.query(),
+            self.featured_themes.query(),
+            self.anime.query(),
+            self.synonyms.query(),
+            self.audio.query(),
+            self.themes.query(),
+            self.entries.query(),
+            self.artists.query(),
+            self.images.query(),
+            self.playlists.query(),
+            self.playlist_tracks.query(),
+            self.resources.query(),
+            self.series.query(),
+            self.songs.query(),
+            self.studios.query(),
+            self.users.query(),
+            self.videos.query(),
+            self.video_scripts.query(),
+        ]
+        .concat()
+    }
+}
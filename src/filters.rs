@@ -0,0 +1,441 @@
+use crate::includes::*;
+use crate::models::{
+    AnimeSeason, ImageFacet, PlaylistVisibility, ThemeType, VideoOverlap, VideoSource,
+};
+
+macro_rules! theme_filter {
+    (
+        pub struct $name: ident ($include: ident) {
+            $($field: ident, $field_cmp: ident: $field_type: ty => $field_name: literal),*
+        }
+    ) => {
+        theme_filter!(@impl $name ($include::include_type()) {
+            $($field, $field_cmp: $field_type => $field_name),*
+        });
+    };
+    (
+        pub struct $name: ident ($filter_type: literal) {
+            $($field: ident, $field_cmp: ident: $field_type: ty => $field_name: literal),*
+        }
+    ) => {
+        theme_filter!(@impl $name ($filter_type) {
+            $($field, $field_cmp: $field_type => $field_name),*
+        });
+    };
+    (
+        @impl $name: ident ($filter_type: expr) {
+            $($field: ident, $field_cmp: ident: $field_type: ty => $field_name: literal),*
+        }
+    ) => {
+        #[derive(Clone, Default, Debug)]
+        pub struct $name {
+            conditions: Vec<FilterCondition>,
+        }
+
+        impl $name {
+            $(
+            pub fn $field(self, value: $field_type) -> Self {
+                self.$field_cmp(Comparison::Eq, value)
+            }
+
+            pub fn $field_cmp(self, comparison: Comparison, value: $field_type) -> Self {
+                self.with_condition(FilterCondition::new($field_name, comparison, value))
+            }
+            )*
+
+            pub fn id<V: FilterValue>(self, value: V) -> Self {
+                self.id_cmp(Comparison::Eq, value)
+            }
+
+            pub fn id_cmp<V: FilterValue>(self, comparison: Comparison, value: V) -> Self {
+                self.with_condition(FilterCondition::new("id", comparison, value))
+            }
+
+            pub fn created_at(self, comparison: Comparison, value: &str) -> Self {
+                self.with_condition(FilterCondition::new("created_at", comparison, value))
+            }
+
+            pub fn updated_at(self, comparison: Comparison, value: &str) -> Self {
+                self.with_condition(FilterCondition::new("updated_at", comparison, value))
+            }
+
+            pub fn deleted_at(self, comparison: Comparison, value: &str) -> Self {
+                self.with_condition(FilterCondition::new("deleted_at", comparison, value))
+            }
+
+            /// Only returns entries that have at least one of the given relation
+            pub fn has(self, relation: &str) -> Self {
+                self.with_condition(FilterCondition::new("has", Comparison::Eq, relation))
+            }
+
+            /// Controls whether soft deleted entries are returned
+            pub fn trashed(self, trashed: Trashed) -> Self {
+                self.with_condition(FilterCondition::new("trashed", Comparison::Eq, trashed))
+            }
+
+            fn with_condition(mut self, condition: FilterCondition) -> Self {
+                self.conditions.push(condition);
+
+                self
+            }
+        }
+
+        impl Filters for $name {
+            fn filter_type() -> &'static str {
+                $filter_type
+            }
+
+            fn conditions(&self) -> &[FilterCondition] {
+                &self.conditions
+            }
+        }
+    }
+}
+
+pub trait Filters {
+    fn filter_type() -> &'static str;
+    fn conditions(&self) -> &[FilterCondition];
+
+    /// Returns the query parameters for the filter.
+    /// Scoped filters are prefixed with the filter type like `filter[anime][year]`
+    /// which is required when filtering search results or included relations.
+    fn query(&self, scoped: bool) -> Vec<(String, String)> {
+        let scope = if scoped {
+            format!("[{}]", Self::filter_type())
+        } else {
+            String::new()
+        };
+
+        self.conditions()
+            .iter()
+            .map(|condition| {
+                let key = match condition.comparison.as_str() {
+                    Some(operator) => format!("filter{scope}[{}][{operator}]", condition.field),
+                    None => format!("filter{scope}[{}]", condition.field),
+                };
+                (key, condition.value.clone())
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Lte,
+    Gte,
+    Like,
+    NotLike,
+}
+
+impl Comparison {
+    /// Returns the operator used in the query or None for plain equality
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            Comparison::Eq => None,
+            Comparison::Ne => Some("ne"),
+            Comparison::Lt => Some("lt"),
+            Comparison::Gt => Some("gt"),
+            Comparison::Lte => Some("lte"),
+            Comparison::Gte => Some("gte"),
+            Comparison::Like => Some("like"),
+            Comparison::NotLike => Some("notlike"),
+        }
+    }
+}
+
+/// Controls whether soft deleted entries are included in the result
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trashed {
+    With,
+    Without,
+    Only,
+}
+
+#[derive(Clone, Debug)]
+pub struct FilterCondition {
+    pub field: &'static str,
+    pub comparison: Comparison,
+    pub value: String,
+}
+
+impl FilterCondition {
+    pub fn new<V: FilterValue>(field: &'static str, comparison: Comparison, value: V) -> Self {
+        Self {
+            field,
+            comparison,
+            value: value.filter_value(),
+        }
+    }
+}
+
+/// A value that can be used in a filter query
+pub trait FilterValue {
+    fn filter_value(&self) -> String;
+}
+
+macro_rules! display_filter_value {
+    ($($value_type: ty),+) => {
+        $(
+        impl FilterValue for $value_type {
+            fn filter_value(&self) -> String {
+                self.to_string()
+            }
+        }
+        )+
+    }
+}
+
+display_filter_value!(u16, u32, u64, &str, String);
+
+impl FilterValue for bool {
+    fn filter_value(&self) -> String {
+        if *self { "true" } else { "false" }.to_string()
+    }
+}
+
+impl FilterValue for Trashed {
+    fn filter_value(&self) -> String {
+        match self {
+            Trashed::With => "with",
+            Trashed::Without => "without",
+            Trashed::Only => "only",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for AnimeSeason {
+    fn filter_value(&self) -> String {
+        match self {
+            AnimeSeason::Winter => "Winter",
+            AnimeSeason::Spring => "Spring",
+            AnimeSeason::Summer => "Summer",
+            AnimeSeason::Fall => "Fall",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for ThemeType {
+    fn filter_value(&self) -> String {
+        match self {
+            ThemeType::OP => "OP",
+            ThemeType::ED => "ED",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for VideoSource {
+    fn filter_value(&self) -> String {
+        match self {
+            VideoSource::WEB => "WEB",
+            VideoSource::RAW => "RAW",
+            VideoSource::BD => "BD",
+            VideoSource::DVD => "DVD",
+            VideoSource::VHS => "VHS",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for VideoOverlap {
+    fn filter_value(&self) -> String {
+        match self {
+            VideoOverlap::None => "None",
+            VideoOverlap::Transition => "Transition",
+            VideoOverlap::Over => "Over",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for PlaylistVisibility {
+    fn filter_value(&self) -> String {
+        match self {
+            PlaylistVisibility::Public => "Public",
+            PlaylistVisibility::Private => "Private",
+            PlaylistVisibility::Unlisted => "Unlisted",
+        }
+        .to_string()
+    }
+}
+
+impl FilterValue for ImageFacet {
+    fn filter_value(&self) -> String {
+        match self {
+            ImageFacet::SmallCover => "Small Cover",
+            ImageFacet::LargeCover => "Large Cover",
+        }
+        .to_string()
+    }
+}
+
+theme_filter!(
+    pub struct AnnouncementFilter ("announcement") {
+        content, content_cmp: &str => "content"
+    }
+);
+
+theme_filter!(
+    pub struct DumpFilter ("dump") {
+        path, path_cmp: &str => "path"
+    }
+);
+
+theme_filter!(
+    pub struct FeaturedThemeFilter (FeaturedThemeInclude) {
+        start_at, start_at_cmp: &str => "start_at",
+        end_at, end_at_cmp: &str => "end_at"
+    }
+);
+
+theme_filter!(
+    pub struct AnimeFilter (AnimeInclude) {
+        name, name_cmp: &str => "name",
+        slug, slug_cmp: &str => "slug",
+        year, year_cmp: u16 => "year",
+        season, season_cmp: AnimeSeason => "season",
+        synopsis, synopsis_cmp: &str => "synopsis"
+    }
+);
+
+theme_filter!(
+    pub struct SynonymFilter (SynonymInclude) {
+        text, text_cmp: &str => "text"
+    }
+);
+
+theme_filter!(
+    pub struct AudioFilter (AudioInclude) {
+        basename, basename_cmp: &str => "basename",
+        filename, filename_cmp: &str => "filename",
+        path, path_cmp: &str => "path",
+        size, size_cmp: u64 => "size",
+        mimetype, mimetype_cmp: &str => "mimetype"
+    }
+);
+
+theme_filter!(
+    pub struct ThemeFilter (ThemeInclude) {
+        theme_type, theme_type_cmp: ThemeType => "type",
+        sequence, sequence_cmp: u16 => "sequence",
+        group, group_cmp: &str => "group",
+        slug, slug_cmp: &str => "slug"
+    }
+);
+
+theme_filter!(
+    pub struct ThemeEntryFilter (ThemeEntryInclude) {
+        version, version_cmp: u32 => "version",
+        episodes, episodes_cmp: &str => "episodes",
+        nsfw, nsfw_cmp: bool => "nsfw",
+        spoiler, spoiler_cmp: bool => "spoiler",
+        notes, notes_cmp: &str => "notes"
+    }
+);
+
+theme_filter!(
+    pub struct ArtistFilter (ArtistInclude) {
+        name, name_cmp: &str => "name",
+        slug, slug_cmp: &str => "slug"
+    }
+);
+
+theme_filter!(
+    pub struct ImageFilter (ImageInclude) {
+        path, path_cmp: &str => "path",
+        facet, facet_cmp: ImageFacet => "facet"
+    }
+);
+
+theme_filter!(
+    pub struct PlaylistFilter (PlaylistInclude) {
+        name, name_cmp: &str => "name",
+        visibility, visibility_cmp: PlaylistVisibility => "visibility",
+        description, description_cmp: &str => "description"
+    }
+);
+
+theme_filter!(
+    pub struct PlaylistTrackFilter (PlaylistTrackInclude) {
+    }
+);
+
+theme_filter!(
+    pub struct ResourceFilter (ResourceInclude) {
+        link, link_cmp: &str => "link",
+        external_id, external_id_cmp: u32 => "external_id",
+        site, site_cmp: &str => "site"
+    }
+);
+
+theme_filter!(
+    pub struct SeriesFilter (SeriesInclude) {
+        name, name_cmp: &str => "name",
+        slug, slug_cmp: &str => "slug"
+    }
+);
+
+theme_filter!(
+    pub struct SongFilter (SongInclude) {
+        title, title_cmp: &str => "title"
+    }
+);
+
+theme_filter!(
+    pub struct StudioFilter (StudioInclude) {
+        name, name_cmp: &str => "name",
+        slug, slug_cmp: &str => "slug"
+    }
+);
+
+theme_filter!(
+    pub struct VideoFilter (VideoInclude) {
+        basename, basename_cmp: &str => "basename",
+        filename, filename_cmp: &str => "filename",
+        path, path_cmp: &str => "path",
+        resolution, resolution_cmp: u32 => "resolution",
+        no_credits, no_credits_cmp: bool => "nc",
+        subbed, subbed_cmp: bool => "subbed",
+        lyrics, lyrics_cmp: bool => "lyrics",
+        uncensored, uncensored_cmp: bool => "uncen",
+        source, source_cmp: VideoSource => "source",
+        overlap, overlap_cmp: VideoOverlap => "overlap",
+        tags, tags_cmp: &str => "tags"
+    }
+);
+
+theme_filter!(
+    pub struct VideoScriptFilter (VideoScriptInclude) {
+        path, path_cmp: &str => "path"
+    }
+);
+
+/// Filters for the results of each type returned by a search
+#[derive(Clone, Default, Debug)]
+pub struct SearchFilters {
+    pub anime: AnimeFilter,
+    pub themes: ThemeFilter,
+    pub artists: ArtistFilter,
+    pub series: SeriesFilter,
+    pub songs: SongFilter,
+    pub videos: VideoFilter,
+}
+
+impl SearchFilters {
+    pub fn query(&self) -> Vec<(String, String)> {
+        let mut query = self.anime.query(true);
+        query.append(&mut self.themes.query(true));
+        query.append(&mut self.artists.query(true));
+        query.append(&mut self.series.query(true));
+        query.append(&mut self.songs.query(true));
+        query.append(&mut self.videos.query(true));
+
+        query
+    }
+}
@@ -75,6 +75,12 @@ theme_include!(
     }
 );
 
+theme_include!(
+    pub struct AudioInclude ("audio") {
+        videos: "videos"
+    }
+);
+
 theme_include!(
     pub struct ThemeInclude ("animetheme") {
         anime: "anime",
@@ -105,6 +111,18 @@ theme_include!(
     }
 );
 
+theme_include!(
+    pub struct FeaturedThemeInclude ("featuredtheme") {
+        entry: "animethemeentry",
+        entry_theme: "animethemeentry.animetheme",
+        entry_theme_anime: "animethemeentry.animetheme.anime",
+        entry_theme_anime_images: "animethemeentry.animetheme.anime.images",
+        entry_theme_song_artists: "animethemeentry.animetheme.song.artists",
+        video: "video",
+        user: "user"
+    }
+);
+
 theme_include!(
     pub struct ImageInclude ("image") {
         anime: "anime",
@@ -113,6 +131,30 @@ theme_include!(
     }
 );
 
+theme_include!(
+    pub struct PlaylistInclude ("playlist") {
+        first: "first",
+        last: "last",
+        images: "images",
+        tracks: "tracks",
+        tracks_video: "tracks.video",
+        tracks_entry: "tracks.animethemeentry",
+        user: "user"
+    }
+);
+
+theme_include!(
+    pub struct PlaylistTrackInclude ("playlisttrack") {
+        playlist: "playlist",
+        previous: "previous",
+        next: "next",
+        video: "video",
+        entry: "animethemeentry",
+        entry_theme: "animethemeentry.animetheme",
+        entry_theme_anime: "animethemeentry.animetheme.anime"
+    }
+);
+
 theme_include!(
     pub struct ResourceInclude ("resource") {
         anime: "anime",
@@ -136,18 +178,35 @@ theme_include!(
 );
 
 theme_include!(
-    pub struct Studio ("studio") {
+    pub struct StudioInclude ("studio") {
         anime: "anime",
         images: "images",
         resources: "resources"
     }
 );
 
+#[deprecated(note = "renamed to StudioInclude")]
+pub type Studio = StudioInclude;
+
+theme_include!(
+    pub struct UserInclude ("user") {
+        playlists: "playlists"
+    }
+);
+
 theme_include!(
     pub struct VideoInclude ("video") {
+        audio: "audio",
         entries: "animethemeentries",
         entries_theme: "animethemeentries.animetheme",
-        entries_theme_anime: "animethemeentries.animetheme.anime"
+        entries_theme_anime: "animethemeentries.animetheme.anime",
+        script: "videoscript"
+    }
+);
+
+theme_include!(
+    pub struct VideoScriptInclude ("videoscript") {
+        video: "video"
     }
 );
 
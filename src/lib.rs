@@ -4,11 +4,22 @@
 //! ```
 //! # use animethemes_rs::error::ApiResult;
 //! use animethemes_rs::client::AnimeThemesClient;
+//! use animethemes_rs::fields::Fieldsets;
+//! use animethemes_rs::filters::SearchFilters;
 //! use animethemes_rs::includes::SearchIncludes;
+//! use animethemes_rs::sort::SearchSorts;
 //!
 //! # async fn a() -> ApiResult<()> {
 //! let client = AnimeThemesClient::default();
-//! let response = client.search("Vivy", &[], SearchIncludes::default()).await?;
+//! let response = client
+//!     .search(
+//!         "Vivy",
+//!         Fieldsets::default(),
+//!         SearchIncludes::default(),
+//!         SearchFilters::default(),
+//!         SearchSorts::default(),
+//!     )
+//!     .await?;
 //!
 //! assert!(response.anime.is_some());
 //! assert!(response.videos.is_some());
@@ -19,7 +30,21 @@
 mod tests;
 mod utils;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod catalogue;
 pub mod client;
+pub mod dump;
 pub mod error;
+pub mod fields;
+pub mod filters;
 pub mod includes;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
 pub mod models;
+pub mod pagination;
+pub mod rate_limit;
+pub mod retry;
+pub mod sort;
+pub mod transport;
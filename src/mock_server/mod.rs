@@ -0,0 +1,341 @@
+//! A local http server imitating the AnimeThemes api.
+//!
+//! The server answers requests from a [Catalogue] the same way the api would, including
+//! the search, show and index endpoints, includes, pagination links and error responses.
+//! It's meant for integration tests of applications using the client without
+//! depending on the network or the data of the api.
+//!
+//! Requires the `mock-server` feature.
+//!
+//! ```
+//! use animethemes_rs::catalogue::Catalogue;
+//! use animethemes_rs::client::AnimeThemesClient;
+//! use animethemes_rs::fields::Fieldsets;
+//! use animethemes_rs::includes::AnimeInclude;
+//! use animethemes_rs::mock_server::MockServer;
+//! use animethemes_rs::models::{Anime, EntryMetadata};
+//! # use animethemes_rs::error::ApiResult;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> ApiResult<()> {
+//! let mut catalogue = Catalogue::new();
+//! catalogue.insert_anime(Anime {
+//!     meta: EntryMetadata {
+//!         id: Some(1),
+//!         ..Default::default()
+//!     },
+//!     name: Some("Bakemonogatari".to_string()),
+//!     slug: Some("bakemonogatari".to_string()),
+//!     ..Default::default()
+//! });
+//! let server = MockServer::start(catalogue)?;
+//! let client = AnimeThemesClient::new(server.url(), "");
+//!
+//! let anime = client
+//!     .anime("bakemonogatari", AnimeInclude::default(), Fieldsets::default())
+//!     .await?;
+//! assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+//! # Ok(()) }
+//! ```
+
+use crate::catalogue::Catalogue;
+use crate::error::ApiResult;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use render::{render, Includes, Kind};
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+mod render;
+
+/// The number of entries per page if no page size is requested
+pub static DEFAULT_PAGE_SIZE: u32 = 15;
+
+/// The largest page size accepted by the api
+pub static MAX_PAGE_SIZE: u32 = 100;
+
+/// The number of results per type returned by the search
+pub static SEARCH_LIMIT: usize = 15;
+
+/// A mock api server listening on a random local port.
+/// The server runs on the tokio runtime it was started on and stops when it's dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts serving the catalogue. Must be called from within a tokio runtime.
+    pub fn start(catalogue: Catalogue) -> ApiResult<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = Arc::new((catalogue, url.clone()));
+
+        let make_service = make_service_fn(move |_| {
+            let state = state.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = state.clone();
+
+                    async move { Ok::<_, Infallible>(respond(&state.0, &state.1, request)) }
+                }))
+            }
+        });
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let server = hyper::Server::from_tcp(listener)
+            .map_err(std::io::Error::other)?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                signal.await.ok();
+            });
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::error!("mock server failed: {e}");
+            }
+        });
+        tracing::debug!("mock server listening on {url}");
+
+        Ok(Self {
+            url,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Returns the url to use as api endpoint of the client
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+fn respond(catalogue: &Catalogue, base: &str, request: Request<Body>) -> Response<Body> {
+    let query: Vec<(String, String)> = Url::parse(&format!("{base}{}", request.uri()))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default();
+    let (status, body) = handle(
+        catalogue,
+        base,
+        request.method(),
+        request.uri().path(),
+        &query,
+    );
+
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("invalid mock response")
+}
+
+/// Returns the status and body the api responds with to a request
+pub(crate) fn handle(
+    catalogue: &Catalogue,
+    base: &str,
+    method: &Method,
+    path: &str,
+    query: &[(String, String)],
+) -> (StatusCode, Value) {
+    if method != Method::GET {
+        return message(
+            StatusCode::METHOD_NOT_ALLOWED,
+            format!("The {method} method is not supported for route {path}."),
+        );
+    }
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match segments[..] {
+        ["search"] => search(catalogue, query),
+        [endpoint] => match Kind::from_endpoint(endpoint) {
+            Some(kind) => index(catalogue, base, kind, query),
+            None => Err(not_found(path)),
+        },
+        [endpoint, key] => match Kind::from_endpoint(endpoint) {
+            Some(kind) => show(catalogue, kind, key, query),
+            None => Err(not_found(path)),
+        },
+        _ => Err(not_found(path)),
+    };
+
+    match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err(error) => error,
+    }
+}
+
+type Handled = Result<Value, (StatusCode, Value)>;
+
+fn search(catalogue: &Catalogue, query: &[(String, String)]) -> Handled {
+    let q = param(query, "q")
+        .filter(|q| !q.trim().is_empty())
+        .ok_or_else(|| validation("q", "The q field is required."))?;
+    let response = catalogue.search(q, SEARCH_LIMIT);
+    let results = [
+        (Kind::Anime, ids(response.anime, |e| e.meta.id)),
+        (Kind::Theme, ids(response.themes, |e| e.meta.id)),
+        (Kind::Artist, ids(response.artists, |e| e.meta.id)),
+        (Kind::Series, ids(response.series, |e| e.meta.id)),
+        (Kind::Song, ids(response.songs, |e| e.meta.id)),
+        (Kind::Video, ids(response.videos, |e| e.meta.id)),
+    ];
+    let mut body = serde_json::Map::new();
+
+    for (kind, ids) in results {
+        let include_key = format!("include[{}]", kind.endpoint());
+        let includes =
+            Includes::parse(kind, param(query, &include_key)).map_err(invalid_include)?;
+        let entries = ids
+            .into_iter()
+            .map(|id| render(catalogue, kind, id, &includes))
+            .collect();
+        body.insert(kind.plural().to_string(), Value::Array(entries));
+    }
+
+    Ok(json!({ "search": body }))
+}
+
+fn show(catalogue: &Catalogue, kind: Kind, key: &str, query: &[(String, String)]) -> Handled {
+    let includes = Includes::parse(kind, param(query, "include")).map_err(invalid_include)?;
+    let id = kind.find(catalogue, key).ok_or_else(|| {
+        message(
+            StatusCode::NOT_FOUND,
+            format!("No query results for {} '{key}'.", kind.endpoint()),
+        )
+    })?;
+
+    Ok(json!({ kind.endpoint(): render(catalogue, kind, id, &includes) }))
+}
+
+fn index(catalogue: &Catalogue, base: &str, kind: Kind, query: &[(String, String)]) -> Handled {
+    let includes = Includes::parse(kind, param(query, "include")).map_err(invalid_include)?;
+    let size = page_param(query, "page[size]", DEFAULT_PAGE_SIZE)?;
+    let number = page_param(query, "page[number]", 1)?;
+
+    if size > MAX_PAGE_SIZE {
+        return Err(validation(
+            "page.size",
+            &format!("The page.size must not be greater than {MAX_PAGE_SIZE}."),
+        ));
+    }
+    // the next page has to be addressable as well
+    let start = (number - 1)
+        .checked_mul(size)
+        .filter(|_| number < u32::MAX)
+        .ok_or_else(|| validation("page.number", "The page.number is too large."))?
+        as usize;
+    let ids = kind.ids(catalogue);
+    let data: Vec<Value> = ids
+        .iter()
+        .skip(start)
+        .take(size as usize)
+        .map(|id| render(catalogue, kind, *id, &includes))
+        .collect();
+    let has_next = start + data.len() < ids.len();
+
+    let path = format!("{base}/{}", kind.endpoint());
+    let link = |number: u32| page_link(&path, query, size, number);
+
+    Ok(json!({
+        kind.plural(): data,
+        "links": {
+            "first": link(1),
+            "last": null,
+            "prev": (number > 1).then(|| link(number - 1)),
+            "next": has_next.then(|| link(number + 1)),
+        },
+        "meta": {
+            "current_page": number,
+            "from": (!data.is_empty()).then_some(start + 1),
+            "path": path,
+            "per_page": size,
+            "to": (!data.is_empty()).then_some(start + data.len()),
+        }
+    }))
+}
+
+/// Builds the link to a page keeping the other query parameters of the request
+fn page_link(path: &str, query: &[(String, String)], size: u32, number: u32) -> String {
+    let params = query
+        .iter()
+        .filter(|(key, _)| !key.starts_with("page["))
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .chain([
+            ("page[size]", size.to_string()),
+            ("page[number]", number.to_string()),
+        ]);
+
+    Url::parse_with_params(path, params)
+        .map(String::from)
+        .unwrap_or_else(|_| path.to_string())
+}
+
+fn param<'a>(query: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    query
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn page_param(
+    query: &[(String, String)],
+    key: &str,
+    default: u32,
+) -> Result<u32, (StatusCode, Value)> {
+    match param(query, key) {
+        None => Ok(default),
+        Some(value) => value.parse().ok().filter(|n| *n > 0).ok_or_else(|| {
+            let field = key.replace('[', ".").replace(']', "");
+            validation(&field, &format!("The {field} must be at least 1."))
+        }),
+    }
+}
+
+fn ids<T, F: Fn(&T) -> Option<u32>>(entries: Option<Vec<T>>, id: F) -> Vec<u32> {
+    entries.iter().flatten().filter_map(id).collect()
+}
+
+fn message(status: StatusCode, message: String) -> (StatusCode, Value) {
+    (status, json!({ "message": message }))
+}
+
+fn not_found(path: &str) -> (StatusCode, Value) {
+    message(
+        StatusCode::NOT_FOUND,
+        format!(
+            "The route {} could not be found.",
+            path.trim_start_matches('/')
+        ),
+    )
+}
+
+fn validation(field: &str, error: &str) -> (StatusCode, Value) {
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        json!({ "message": error, "errors": { field: [error] } }),
+    )
+}
+
+fn invalid_include(detail: String) -> (StatusCode, Value) {
+    (
+        StatusCode::BAD_REQUEST,
+        json!({
+            "errors": [{
+                "status": "400",
+                "title": "Invalid Include",
+                "detail": detail,
+            }]
+        }),
+    )
+}
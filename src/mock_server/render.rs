@@ -0,0 +1,369 @@
+use crate::catalogue::Catalogue;
+use crate::models::EntryMetadata;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The types of entries served by the mock server
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Anime,
+    Synonym,
+    Series,
+    Theme,
+    Entry,
+    Video,
+    Song,
+    Artist,
+}
+
+static KINDS: [Kind; 8] = [
+    Kind::Anime,
+    Kind::Synonym,
+    Kind::Series,
+    Kind::Theme,
+    Kind::Entry,
+    Kind::Video,
+    Kind::Song,
+    Kind::Artist,
+];
+
+impl Kind {
+    /// Returns the kind served by an endpoint like `/anime`
+    pub fn from_endpoint(endpoint: &str) -> Option<Self> {
+        KINDS.iter().copied().find(|k| k.endpoint() == endpoint)
+    }
+
+    /// The name of the endpoint, also used as the key of a single entry
+    /// and the type of search includes
+    pub fn endpoint(self) -> &'static str {
+        match self {
+            Kind::Anime => "anime",
+            Kind::Synonym => "animesynonym",
+            Kind::Series => "series",
+            Kind::Theme => "animetheme",
+            Kind::Entry => "animethemeentry",
+            Kind::Video => "video",
+            Kind::Song => "song",
+            Kind::Artist => "artist",
+        }
+    }
+
+    /// The key of a list of entries
+    pub fn plural(self) -> &'static str {
+        match self {
+            Kind::Anime => "anime",
+            Kind::Synonym => "animesynonyms",
+            Kind::Series => "series",
+            Kind::Theme => "animethemes",
+            Kind::Entry => "animethemeentries",
+            Kind::Video => "videos",
+            Kind::Song => "songs",
+            Kind::Artist => "artists",
+        }
+    }
+
+    /// The relations that can be included with their kind and whether they're a list.
+    /// Relations without a kind aren't stored in the catalogue and are always empty.
+    fn relations(self) -> &'static [(&'static str, Option<Kind>, bool)] {
+        match self {
+            Kind::Anime => &[
+                ("animesynonyms", Some(Kind::Synonym), true),
+                ("animethemes", Some(Kind::Theme), true),
+                ("images", None, true),
+                ("resources", None, true),
+                ("series", Some(Kind::Series), true),
+                ("studios", None, true),
+            ],
+            Kind::Synonym => &[("anime", Some(Kind::Anime), false)],
+            Kind::Series => &[("anime", Some(Kind::Anime), true)],
+            Kind::Theme => &[
+                ("anime", Some(Kind::Anime), false),
+                ("animethemeentries", Some(Kind::Entry), true),
+                ("song", Some(Kind::Song), false),
+            ],
+            Kind::Entry => &[
+                ("animetheme", Some(Kind::Theme), false),
+                ("videos", Some(Kind::Video), true),
+            ],
+            Kind::Video => &[
+                ("animethemeentries", Some(Kind::Entry), true),
+                ("audio", None, false),
+                ("videoscript", None, false),
+            ],
+            Kind::Song => &[
+                ("animethemes", Some(Kind::Theme), true),
+                ("artists", Some(Kind::Artist), true),
+            ],
+            Kind::Artist => &[
+                ("groups", None, true),
+                ("members", None, true),
+                ("resources", None, true),
+                ("songs", Some(Kind::Song), true),
+            ],
+        }
+    }
+
+    /// Returns the ids of all entries sorted ascending
+    pub fn ids(self, catalogue: &Catalogue) -> Vec<u32> {
+        let mut ids: Vec<u32> = match self {
+            Kind::Anime => catalogue.all_anime().filter_map(|e| e.meta.id).collect(),
+            Kind::Synonym => catalogue.all_synonyms().filter_map(|e| e.meta.id).collect(),
+            Kind::Series => catalogue.all_series().filter_map(|e| e.meta.id).collect(),
+            Kind::Theme => catalogue.all_themes().filter_map(|e| e.meta.id).collect(),
+            Kind::Entry => catalogue.all_entries().filter_map(|e| e.meta.id).collect(),
+            Kind::Video => catalogue.all_videos().filter_map(|e| e.meta.id).collect(),
+            Kind::Song => catalogue.all_songs().filter_map(|e| e.meta.id).collect(),
+            Kind::Artist => catalogue.all_artists().filter_map(|e| e.meta.id).collect(),
+        };
+        ids.sort_unstable();
+
+        ids
+    }
+
+    /// Finds an entry by the key used in the url of the show endpoint
+    pub fn find(self, catalogue: &Catalogue, key: &str) -> Option<u32> {
+        match self {
+            Kind::Anime => catalogue
+                .all_anime()
+                .find(|e| e.slug.as_deref() == Some(key))
+                .and_then(|e| e.meta.id),
+            Kind::Series => catalogue
+                .all_series()
+                .find(|e| e.slug.as_deref() == Some(key))
+                .and_then(|e| e.meta.id),
+            Kind::Artist => catalogue
+                .all_artists()
+                .find(|e| e.slug.as_deref() == Some(key))
+                .and_then(|e| e.meta.id),
+            Kind::Video => catalogue.video_by_basename(key).and_then(|e| e.meta.id),
+            _ => {
+                let id = key.parse().ok()?;
+                self.attributes(catalogue, id).map(|_| id)
+            }
+        }
+    }
+
+    /// Returns the attributes of an entry as they're returned by the api
+    fn attributes(self, catalogue: &Catalogue, id: u32) -> Option<Map<String, Value>> {
+        let (meta, attributes) = match self {
+            Kind::Anime => catalogue.anime(id).map(|e| {
+                (
+                    &e.meta,
+                    json!({
+                        "name": e.name,
+                        "slug": e.slug,
+                        "year": e.year,
+                        "season": e.season.map(|s| format!("{s:?}")),
+                        "synopsis": e.synopsis,
+                    }),
+                )
+            }),
+            Kind::Synonym => catalogue
+                .synonym(id)
+                .map(|e| (&e.meta, json!({ "text": e.text }))),
+            Kind::Series => catalogue
+                .series(id)
+                .map(|e| (&e.meta, json!({ "name": e.name, "slug": e.slug }))),
+            Kind::Theme => catalogue.theme(id).map(|e| {
+                (
+                    &e.meta,
+                    json!({
+                        "type": e.theme_type.as_ref().map(|t| format!("{t:?}")),
+                        "sequence": e.sequence,
+                        "group": e.group,
+                        "slug": e.slug,
+                    }),
+                )
+            }),
+            Kind::Entry => catalogue.entry(id).map(|e| {
+                (
+                    &e.meta,
+                    json!({
+                        "version": e.version,
+                        "episodes": e.episodes,
+                        "nsfw": e.nsfw,
+                        "spoiler": e.spoiler,
+                        "notes": e.notes,
+                    }),
+                )
+            }),
+            Kind::Video => catalogue.video(id).map(|e| {
+                (
+                    &e.meta,
+                    json!({
+                        "basename": e.basename,
+                        "filename": e.filename,
+                        "path": e.path,
+                        "resolution": e.resolution,
+                        "nc": e.no_credits,
+                        "subbed": e.subbed,
+                        "lyrics": e.lyrics,
+                        "uncen": e.uncensored,
+                        "source": e.source.as_ref().map(|s| format!("{s:?}")),
+                        "overlap": e.overlap.as_ref().map(|o| format!("{o:?}")),
+                        "tags": e.tags,
+                        "link": e.link,
+                    }),
+                )
+            }),
+            Kind::Song => catalogue
+                .song(id)
+                .map(|e| (&e.meta, json!({ "title": e.title }))),
+            Kind::Artist => catalogue
+                .artist(id)
+                .map(|e| (&e.meta, json!({ "name": e.name, "slug": e.slug }))),
+        }?;
+
+        let mut map = metadata(meta);
+        if let Value::Object(attributes) = attributes {
+            map.extend(attributes);
+        }
+
+        Some(map)
+    }
+
+    fn related(self, catalogue: &Catalogue, id: u32, relation: &str) -> Vec<u32> {
+        match (self, relation) {
+            (Kind::Anime, "animesynonyms") => ids(catalogue.synonyms_of(id), |e| e.meta.id),
+            (Kind::Anime, "animethemes") => ids(catalogue.themes_of(id), |e| e.meta.id),
+            (Kind::Anime, "series") => ids(catalogue.series_of(id), |e| e.meta.id),
+            (Kind::Synonym, "anime") => catalogue
+                .all_anime()
+                .filter_map(|a| a.meta.id)
+                .filter(|&anime| {
+                    catalogue
+                        .synonyms_of(anime)
+                        .iter()
+                        .any(|s| s.meta.id == Some(id))
+                })
+                .collect(),
+            (Kind::Series, "anime") => ids(catalogue.anime_of_series(id), |e| e.meta.id),
+            (Kind::Theme, "anime") => catalogue
+                .anime_of_theme(id)
+                .and_then(|e| e.meta.id)
+                .into_iter()
+                .collect(),
+            (Kind::Theme, "animethemeentries") => ids(catalogue.entries_of(id), |e| e.meta.id),
+            (Kind::Theme, "song") => catalogue
+                .song_of(id)
+                .and_then(|e| e.meta.id)
+                .into_iter()
+                .collect(),
+            (Kind::Entry, "animetheme") => catalogue
+                .theme_of_entry(id)
+                .and_then(|e| e.meta.id)
+                .into_iter()
+                .collect(),
+            (Kind::Entry, "videos") => ids(catalogue.videos_of(id), |e| e.meta.id),
+            (Kind::Video, "animethemeentries") => {
+                ids(catalogue.entries_of_video(id), |e| e.meta.id)
+            }
+            (Kind::Song, "animethemes") => ids(catalogue.themes_of_song(id), |e| e.meta.id),
+            (Kind::Song, "artists") => ids(catalogue.artists_of(id), |e| e.meta.id),
+            (Kind::Artist, "songs") => ids(catalogue.songs_of(id), |e| e.meta.id),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn ids<T, F: Fn(&T) -> Option<u32>>(entries: Vec<&T>, id: F) -> Vec<u32> {
+    entries.into_iter().filter_map(id).collect()
+}
+
+fn metadata(meta: &EntryMetadata) -> Map<String, Value> {
+    let mut map = Map::new();
+    map.insert("id".into(), meta.id.into());
+    map.insert("created_at".into(), meta.created_at.clone().into());
+    map.insert("updated_at".into(), meta.updated_at.clone().into());
+    map.insert("deleted_at".into(), meta.deleted_at.clone().into());
+
+    map
+}
+
+/// The relations to include as a tree of dotted paths like `animethemes.song.artists`
+#[derive(Debug, Default)]
+pub(crate) struct Includes(BTreeMap<String, Includes>);
+
+impl Includes {
+    /// Parses a comma separated list of include paths and checks that
+    /// every relation exists for the kind
+    pub fn parse(kind: Kind, value: Option<&str>) -> Result<Self, String> {
+        let mut includes = Includes::default();
+
+        for path in value.into_iter().flat_map(|v| v.split(',')) {
+            let mut current = &mut includes;
+            let mut current_kind = Some(kind);
+
+            for relation in path.split('.') {
+                let related_kind = current_kind
+                    .and_then(|k| k.relations().iter().find(|(name, _, _)| *name == relation))
+                    .map(|(_, related_kind, _)| *related_kind)
+                    .ok_or_else(|| {
+                        format!(
+                            "Requested include '{path}' is not allowed. Allowed includes are '{}'.",
+                            allowed(kind)
+                        )
+                    })?;
+                current_kind = related_kind;
+                current = current.0.entry(relation.to_string()).or_default();
+            }
+        }
+
+        Ok(includes)
+    }
+}
+
+fn allowed(kind: Kind) -> String {
+    kind.relations()
+        .iter()
+        .map(|(name, _, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Renders an entry with its included relations
+pub(crate) fn render(catalogue: &Catalogue, kind: Kind, id: u32, includes: &Includes) -> Value {
+    let Some(mut entry) = kind.attributes(catalogue, id) else {
+        return Value::Null;
+    };
+
+    for (relation, children) in &includes.0 {
+        let Some((_, related_kind, many)) = kind
+            .relations()
+            .iter()
+            .find(|(name, _, _)| name == relation)
+        else {
+            continue;
+        };
+        let mut related = match related_kind {
+            Some(related_kind) => kind
+                .related(catalogue, id, relation)
+                .into_iter()
+                .map(|related_id| {
+                    let mut value = render(catalogue, *related_kind, related_id, children);
+
+                    let credit = (*related_kind == Kind::Artist)
+                        .then(|| catalogue.credited_character(id, related_id))
+                        .flatten();
+
+                    if let Some(character) = credit {
+                        value["as"] = character.into();
+                    }
+                    value
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let value = if *many {
+            Value::Array(related)
+        } else if related.is_empty() {
+            Value::Null
+        } else {
+            related.swap_remove(0)
+        };
+        entry.insert(relation.clone(), value);
+    }
+
+    Value::Object(entry)
+}
@@ -1,21 +1,25 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, Deserialize)]
+// Attributes can be omitted by the api when sparse fieldsets are requested.
+// Therefore all attributes are optional, so omitted attributes can be told apart from real values.
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct EntryMetadata {
-    pub id: u32,
-    pub created_at: String,
-    pub updated_at: String,
+    pub id: Option<u32>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
     pub deleted_at: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Anime {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub name: String,
-    pub slug: String,
-    pub year: u16,
-    pub season: AnimeSeason,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub year: Option<u16>,
+    pub season: Option<AnimeSeason>,
     pub synopsis: Option<String>,
     #[serde(alias = "animesynonyms")]
     pub synonyms: Option<Vec<AnimeSynonym>>,
@@ -24,9 +28,10 @@ pub struct Anime {
     pub series: Option<Vec<Series>>,
     pub resource: Option<Vec<Resource>>,
     pub images: Option<Vec<Image>>,
+    pub studios: Option<Vec<Studio>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum AnimeSeason {
     Winter,
     Spring,
@@ -38,20 +43,20 @@ pub enum AnimeSeason {
 pub struct AnimeSynonym {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub text: String,
+    pub text: Option<String>,
     pub anime: Option<Anime>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Theme {
     #[serde(flatten)]
     pub meta: EntryMetadata,
     #[serde(alias = "type")]
-    pub theme_type: ThemeType,
-    #[serde(deserialize_with = "crate::utils::empty_string_as_none")]
+    pub theme_type: Option<ThemeType>,
+    #[serde(default, deserialize_with = "crate::utils::empty_string_as_none")]
     pub sequence: Option<u16>,
     pub group: Option<String>,
-    pub slug: String,
+    pub slug: Option<String>,
     pub song: Option<Song>,
     pub anime: Option<Anime>,
     #[serde(alias = "animethemeentries")]
@@ -64,63 +69,146 @@ pub enum ThemeType {
     ED,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Song {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub title: String,
+    pub title: Option<String>,
     pub artists: Option<Vec<Artist>>,
     #[serde(alias = "animethemes")]
     pub themes: Option<Vec<Theme>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Artist {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub name: String,
-    pub slug: String,
+    pub name: Option<String>,
+    pub slug: Option<String>,
     #[serde(alias = "as")]
     pub as_character: Option<String>,
     pub songs: Option<Vec<Song>>,
+    pub groups: Option<Vec<Artist>>,
+    pub members: Option<Vec<Artist>>,
+    /// The membership attributes when the artist is related as group or member of another artist
+    #[serde(alias = "artistmember")]
+    pub membership: Option<ArtistMembership>,
 }
 
+impl Artist {
+    /// Returns the name the artist is credited with in a membership
+    /// or the name of the artist if there's no alias
+    pub fn credited_name(&self) -> Option<&str> {
+        self.membership
+            .as_ref()
+            .and_then(|m| m.alias.as_deref())
+            .or(self.name.as_deref())
+    }
+
+    /// Returns the groups the artist is a member of.
+    /// Requires the `groups` include.
+    pub fn member_of(&self) -> impl Iterator<Item = &Artist> {
+        self.groups.iter().flatten()
+    }
+
+    /// Returns the members of the artist if it's a group.
+    /// Requires the `members` include.
+    pub fn group_members(&self) -> impl Iterator<Item = &Artist> {
+        self.members.iter().flatten()
+    }
+
+    /// Returns descriptions of all group memberships like `member of ClariS`.
+    /// Requires the `groups` include. Groups without a name are skipped.
+    pub fn membership_credits(&self) -> Vec<String> {
+        self.member_of()
+            .filter_map(|group| {
+                let mut credit = format!("member of {}", group.name.as_ref()?);
+                let membership = group.membership.as_ref();
+
+                if let Some(alias) = membership.and_then(|m| m.alias.as_ref()) {
+                    credit.push_str(&format!(" as {alias}"));
+                }
+                if let Some(character) = membership.and_then(|m| m.as_character.as_ref()) {
+                    credit.push_str(&format!(" (as {character})"));
+                }
+
+                Some(credit)
+            })
+            .collect()
+    }
+}
+
+/// The pivot between a group and one of its members
 #[derive(Debug, Clone, Deserialize)]
+pub struct ArtistMembership {
+    pub alias: Option<String>,
+    #[serde(alias = "as")]
+    pub as_character: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct ThemeEntry {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    #[serde(deserialize_with = "crate::utils::empty_string_as_none")]
+    #[serde(default, deserialize_with = "crate::utils::empty_string_as_none")]
     pub version: Option<u32>,
     pub episodes: Option<String>,
-    pub nsfw: bool,
-    pub spoiler: bool,
+    pub nsfw: Option<bool>,
+    pub spoiler: Option<bool>,
     pub notes: Option<String>,
     pub videos: Option<Vec<Video>>,
     #[serde(alias = "animetheme")]
     pub theme: Option<Theme>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Video {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub basename: String,
-    pub filename: String,
-    pub path: String,
-    pub resolution: u32,
+    pub basename: Option<String>,
+    pub filename: Option<String>,
+    pub path: Option<String>,
+    pub resolution: Option<u32>,
     #[serde(alias = "nc")]
-    pub no_credits: bool,
-    pub subbed: bool,
-    pub lyrics: bool,
+    pub no_credits: Option<bool>,
+    pub subbed: Option<bool>,
+    pub lyrics: Option<bool>,
     #[serde(alias = "uncen")]
-    pub uncensored: bool,
-    #[serde(deserialize_with = "crate::utils::empty_string_as_none")]
+    pub uncensored: Option<bool>,
+    #[serde(default, deserialize_with = "crate::utils::empty_string_as_none")]
     pub source: Option<VideoSource>,
-    pub overlap: VideoOverlap,
-    pub link: String,
+    pub overlap: Option<VideoOverlap>,
+    pub link: Option<String>,
     #[serde(alias = "animethemeentries")]
     pub entries: Option<Vec<ThemeEntry>>,
     pub tags: Option<String>,
+    pub audio: Option<Audio>,
+    #[serde(alias = "videoscript")]
+    pub script: Option<VideoScript>,
+}
+
+/// The encoding script that was used to create a video
+#[derive(Debug, Clone, Deserialize)]
+pub struct VideoScript {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub path: Option<String>,
+    pub link: Option<String>,
+    pub video: Option<Box<Video>>,
+}
+
+/// The audio track extracted from a video
+#[derive(Debug, Clone, Deserialize)]
+pub struct Audio {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub basename: Option<String>,
+    pub filename: Option<String>,
+    pub path: Option<String>,
+    pub size: Option<u64>,
+    pub mimetype: Option<String>,
+    pub link: Option<String>,
+    pub videos: Option<Vec<Video>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -143,8 +231,8 @@ pub enum VideoOverlap {
 pub struct Series {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub name: String,
-    pub slug: String,
+    pub name: Option<String>,
+    pub slug: Option<String>,
     pub anime: Option<Vec<Anime>>,
 }
 
@@ -152,23 +240,25 @@ pub struct Series {
 pub struct Resource {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub link: String,
-    pub external_id: u32,
-    pub site: String,
+    pub link: Option<String>,
+    pub external_id: Option<u32>,
+    pub site: Option<String>,
     #[serde(alias = "as")]
     pub resource_as: Option<String>,
     pub anime: Option<Vec<Anime>>,
     pub artists: Option<Vec<Artist>>,
+    pub studios: Option<Vec<Studio>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Image {
     #[serde(flatten)]
     pub meta: EntryMetadata,
-    pub path: String,
-    pub facet: ImageFacet,
+    pub path: Option<String>,
+    pub facet: Option<ImageFacet>,
     pub anime: Option<Vec<Anime>>,
     pub artists: Option<Vec<Artist>>,
+    pub studios: Option<Vec<Studio>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -180,6 +270,247 @@ pub enum ImageFacet {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+pub struct Studio {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub anime: Option<Vec<Anime>>,
+    pub images: Option<Vec<Image>>,
+    pub resources: Option<Vec<Resource>>,
+}
+
+/// The anime of a year grouped by season
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnimeYear {
+    #[serde(default, alias = "Winter")]
+    pub winter: Vec<Anime>,
+    #[serde(default, alias = "Spring")]
+    pub spring: Vec<Anime>,
+    #[serde(default, alias = "Summer")]
+    pub summer: Vec<Anime>,
+    #[serde(default, alias = "Fall")]
+    pub fall: Vec<Anime>,
+}
+
+impl AnimeYear {
+    /// Returns the anime of the given season
+    pub fn season(&self, season: AnimeSeason) -> &[Anime] {
+        match season {
+            AnimeSeason::Winter => &self.winter,
+            AnimeSeason::Spring => &self.spring,
+            AnimeSeason::Summer => &self.summer,
+            AnimeSeason::Fall => &self.fall,
+        }
+    }
+
+    /// Returns all seasons in chronological order with their anime
+    pub fn seasons(&self) -> [(AnimeSeason, &[Anime]); 4] {
+        [
+            (AnimeSeason::Winter, &self.winter),
+            (AnimeSeason::Spring, &self.spring),
+            (AnimeSeason::Summer, &self.summer),
+            (AnimeSeason::Fall, &self.fall),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Announcement {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub content: Option<String>,
+}
+
+/// A database dump published by AnimeThemes
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dump {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub path: Option<String>,
+    pub link: Option<String>,
+}
+
+/// A theme that is featured on the AnimeThemes homepage for a period of time
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeaturedTheme {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub start_at: Option<String>,
+    pub end_at: Option<String>,
+    #[serde(alias = "animethemeentry")]
+    pub entry: Option<ThemeEntry>,
+    pub video: Option<Video>,
+    pub user: Option<User>,
+}
+
+/// A registered user of AnimeThemes
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    #[serde(flatten)]
+    pub meta: EntryMetadata,
+    pub name: Option<String>,
+    /// Only returned for the authenticated user
+    pub email: Option<String>,
+    pub email_verified_at: Option<String>,
+    pub playlists: Option<Vec<Playlist>>,
+}
+
+/// Metadata of entries that are identified by a hashid instead of a numeric id
+#[derive(Debug, Clone, Deserialize)]
+pub struct HashidMetadata {
+    pub id: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Playlist {
+    #[serde(flatten)]
+    pub meta: HashidMetadata,
+    pub name: Option<String>,
+    pub visibility: Option<PlaylistVisibility>,
+    pub description: Option<String>,
+    pub tracks_count: Option<u32>,
+    pub first: Option<Box<PlaylistTrack>>,
+    pub last: Option<Box<PlaylistTrack>>,
+    pub tracks: Option<Vec<PlaylistTrack>>,
+    pub images: Option<Vec<Image>>,
+    pub user: Option<Box<User>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlaylistVisibility {
+    Public,
+    Private,
+    Unlisted,
+}
+
+/// The attributes of a playlist that is created
+#[derive(Debug, Clone, Serialize)]
+pub struct NewPlaylist {
+    pub name: String,
+    pub visibility: PlaylistVisibility,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The attributes of a playlist that are changed. Unset attributes stay unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaylistUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<PlaylistVisibility>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlaylistTrack {
+    #[serde(flatten)]
+    pub meta: HashidMetadata,
+    pub playlist: Option<Box<Playlist>>,
+    pub previous: Option<Box<PlaylistTrack>>,
+    pub next: Option<Box<PlaylistTrack>>,
+    pub video: Option<Video>,
+    #[serde(alias = "animethemeentry")]
+    pub entry: Option<ThemeEntry>,
+}
+
+/// A track that is added to a playlist.
+/// Without a previous or next track, the track is appended to the end of the playlist.
+#[derive(Debug, Clone, Serialize)]
+pub struct NewPlaylistTrack {
+    pub video_id: u32,
+    pub entry_id: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+/// The attributes of a playlist track that are changed. Unset attributes stay unchanged.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaylistTrackUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+}
+
+impl PlaylistTrack {
+    /// Orders tracks by following their `previous` and `next` links starting at the
+    /// track without a predecessor. Requires the `previous` or `next` include.
+    /// A missing `next` link is taken from the `previous` link of the following track.
+    /// Tracks that aren't reachable from the first track or have no id are appended afterwards.
+    pub fn playback_order(tracks: Vec<PlaylistTrack>) -> Vec<PlaylistTrack> {
+        let id = |track: &PlaylistTrack| track.meta.id.clone();
+        let ids: HashSet<String> = tracks.iter().filter_map(id).collect();
+        let mut successors: HashMap<String, String> = tracks
+            .iter()
+            .filter_map(|track| Some((id(track)?, id(track.next.as_ref()?)?)))
+            .collect();
+
+        for track in &tracks {
+            if let (Some(previous), Some(current)) = (track.previous.as_deref(), id(track)) {
+                if let Some(previous) = id(previous) {
+                    successors.entry(previous).or_insert(current);
+                }
+            }
+        }
+        let linked: HashSet<&String> = successors
+            .iter()
+            .filter(|(id, _)| ids.contains(*id))
+            .map(|(_, next)| next)
+            .collect();
+        let mut heads: Vec<String> = tracks
+            .iter()
+            .filter_map(id)
+            .filter(|id| !linked.contains(id))
+            .collect();
+        // the start of the playlist is the head of the chain, single tracks come afterwards
+        heads.sort_by_key(|id| !successors.contains_key(id));
+
+        let mut order = Vec::with_capacity(tracks.len());
+        let mut visited = HashSet::new();
+
+        for head in heads {
+            let mut current = Some(head);
+
+            while let Some(id) = current {
+                if !visited.insert(id.clone()) {
+                    break;
+                }
+                current = successors.get(&id).cloned();
+                order.push(id);
+            }
+        }
+        let mut tracks_by_id: HashMap<String, PlaylistTrack> = HashMap::new();
+        let mut unlinked = Vec::new();
+
+        for track in tracks {
+            match id(&track).filter(|id| visited.contains(id)) {
+                Some(id) => {
+                    tracks_by_id.insert(id, track);
+                }
+                None => unlinked.push(track),
+            }
+        }
+        order
+            .into_iter()
+            .filter_map(|id| tracks_by_id.remove(&id))
+            .chain(unlinked)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct SearchResponse {
     pub anime: Option<Vec<Anime>>,
     pub artists: Option<Vec<Artist>>,
@@ -189,3 +520,37 @@ pub struct SearchResponse {
     pub themes: Option<Vec<Theme>>,
     pub videos: Option<Vec<Video>>,
 }
+
+/// A single page of an index endpoint
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub links: PageLinks,
+    pub meta: PageMeta,
+}
+
+impl<T> Page<T> {
+    /// Returns if there's a page after this one
+    pub fn has_next(&self) -> bool {
+        self.links.next.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PageLinks {
+    pub first: Option<String>,
+    pub last: Option<String>,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PageMeta {
+    pub current_page: u32,
+    pub per_page: u32,
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+    pub path: Option<String>,
+    pub last_page: Option<u32>,
+    pub total: Option<u64>,
+}
@@ -0,0 +1,95 @@
+/// Controls which page of an index endpoint is requested
+///
+/// ```
+/// use animethemes_rs::pagination::Pagination;
+///
+/// let pagination = Pagination::default().size(50).number(2);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pagination {
+    pub size: Option<u32>,
+    pub number: Option<u32>,
+}
+
+impl Pagination {
+    /// Creates a new pagination for the given page size and number
+    pub fn new(size: u32, number: u32) -> Self {
+        Self {
+            size: Some(size),
+            number: Some(number),
+        }
+    }
+
+    /// Sets the number of items per page
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size);
+
+        self
+    }
+
+    /// Sets the number of the page starting at 1
+    pub fn number(mut self, number: u32) -> Self {
+        self.number = Some(number);
+
+        self
+    }
+
+    pub fn query(&self) -> Vec<(String, String)> {
+        let mut query = Vec::new();
+
+        if let Some(size) = self.size {
+            query.push(("page[size]".to_string(), size.to_string()));
+        }
+        if let Some(number) = self.number {
+            query.push(("page[number]".to_string(), number.to_string()));
+        }
+
+        query
+    }
+}
+
+/// Controls how [AnimeThemesClient::paginate](crate::client::AnimeThemesClient::paginate)
+/// walks through the pages of an index endpoint
+#[derive(Clone, Copy, Debug)]
+pub struct PaginateOptions {
+    /// The number of items requested per page. Uses the api default if unset
+    pub page_size: Option<u32>,
+    /// The number of pages that are buffered ahead of the consumer.
+    /// Pages are still requested one after another as each page links to the next one
+    pub concurrency: usize,
+    /// The maximum number of items yielded by the stream
+    pub max_items: Option<usize>,
+}
+
+impl Default for PaginateOptions {
+    fn default() -> Self {
+        Self {
+            page_size: None,
+            concurrency: 1,
+            max_items: None,
+        }
+    }
+}
+
+impl PaginateOptions {
+    /// Sets the number of items requested per page
+    pub fn page_size(mut self, size: u32) -> Self {
+        self.page_size = Some(size);
+
+        self
+    }
+
+    /// Sets the number of pages that are buffered ahead of the consumer
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+
+        self
+    }
+
+    /// Stops the stream after the given number of items
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+
+        self
+    }
+}
@@ -0,0 +1,159 @@
+//! Client side rate limiting.
+//!
+//! The api limits the number of requests per minute for each ip. The [RateLimiter] is a
+//! token bucket that delays requests before the limit is reached instead of letting them fail.
+//! It adapts to the `X-RateLimit-Remaining` and `Retry-After` headers returned by the api.
+//! Waiting requests are sent in the order they were started.
+//! Clients only limit their requests when a limiter is configured with
+//! [AnimeThemesClientBuilder::rate_limit](crate::client::AnimeThemesClientBuilder::rate_limit).
+
+use crate::error::parse_retry_after;
+use reqwest::header::HeaderMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The number of requests per minute allowed by the api
+pub static DEFAULT_REQUESTS_PER_MINUTE: u32 = 90;
+
+static RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+
+/// A token bucket limiting the requests of a client. Clones share the same bucket.
+///
+/// ```
+/// # use animethemes_rs::error::ApiResult;
+/// use animethemes_rs::client::AnimeThemesClient;
+/// use animethemes_rs::rate_limit::RateLimiter;
+///
+/// # fn a() -> ApiResult<()> {
+/// let client = AnimeThemesClient::builder()
+///     .rate_limit(RateLimiter::per_minute(60))
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    queue: Arc<tokio::sync::Mutex<()>>,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    /// tokens added per second
+    refill_rate: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Takes a token or returns how long to wait until one is available
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+
+        if let Some(blocked_until) = self.blocked_until {
+            if blocked_until > now {
+                return Some(blocked_until - now);
+            }
+            self.blocked_until = None;
+        }
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_rate,
+            ))
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing bursts of up to `requests` that refill over the given period
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let capacity = requests.max(1) as f64;
+
+        Self {
+            queue: Arc::default(),
+            bucket: Arc::new(Mutex::new(Bucket {
+                capacity,
+                tokens: capacity,
+                refill_rate: capacity / period.as_secs_f64().max(f64::EPSILON),
+                last_refill: Instant::now(),
+                blocked_until: None,
+            })),
+        }
+    }
+
+    /// Creates a limiter allowing the given number of requests per minute
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// Returns the number of requests that can currently be sent without waiting
+    pub fn available(&self) -> u32 {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+
+        bucket.tokens as u32
+    }
+
+    /// Waits until a request can be sent.
+    /// Waiting requests are queued and continue in the order they called this method.
+    pub(crate) async fn acquire(&self) {
+        let _ticket = self.queue.lock().await;
+
+        loop {
+            // the bucket might change while waiting so the wait time is calculated again afterwards
+            let wait = self.bucket.lock().unwrap().take();
+
+            match wait {
+                Some(duration) => {
+                    tracing::debug!("rate limit reached, waiting {duration:?}");
+                    tokio::time::sleep(duration).await
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Adapts the bucket to the rate limit headers of a response
+    pub(crate) fn update(&self, headers: &HeaderMap) {
+        let remaining = header_number(headers, RATE_LIMIT_REMAINING);
+        let retry_after = parse_retry_after(headers);
+
+        if remaining.is_none() && retry_after.is_none() {
+            return;
+        }
+        let mut bucket = self.bucket.lock().unwrap();
+
+        if let Some(remaining) = remaining {
+            bucket.tokens = bucket.tokens.min(remaining as f64);
+        }
+        if let Some(retry_after) = retry_after {
+            bucket.tokens = 0.0;
+            bucket.blocked_until = Some(Instant::now() + retry_after);
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::per_minute(DEFAULT_REQUESTS_PER_MINUTE)
+    }
+}
+
+fn header_number(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
@@ -0,0 +1,176 @@
+//! Retrying of failed requests.
+//!
+//! Requests failing with a retryable status or a connection error are sent again after
+//! an exponentially growing delay with random jitter. If the api returns a `Retry-After`
+//! header, the delay requested by the api is used instead. Requests the api asks to
+//! delay for longer than the maximum backoff aren't retried.
+//! Only idempotent requests are retried, so creating entries is never repeated.
+
+use crate::error::ApiError;
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// Configures when and how often failed requests are retried
+///
+/// ```
+/// # use animethemes_rs::error::ApiResult;
+/// use animethemes_rs::client::AnimeThemesClient;
+/// use animethemes_rs::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// # fn a() -> ApiResult<()> {
+/// let client = AnimeThemesClient::builder()
+///     .retry(
+///         RetryPolicy::default()
+///             .max_attempts(5)
+///             .backoff(Duration::from_secs(1), Duration::from_secs(60)),
+///     )
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    statuses: Vec<StatusCode>,
+    connection_errors: bool,
+    timeouts: bool,
+    respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Retries up to two times on connection errors, timeouts, 429 and 5xx gateway errors
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            connection_errors: true,
+            timeouts: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Sets how often a request is sent at most, including the first attempt
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+
+        self
+    }
+
+    /// Sets the delay before the first retry and the upper limit for the delay.
+    /// Requests are given up if the `Retry-After` header asks for a longer delay.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+
+        self
+    }
+
+    /// Sets the factor the delay grows by after each attempt
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+
+        self
+    }
+
+    /// Sets the fraction of the delay that is randomized between 0 and 1.
+    /// With a jitter of 0.5 the delay is between half and all of the calculated backoff.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+
+        self
+    }
+
+    /// Sets the response statuses that are retried
+    pub fn statuses<I: IntoIterator<Item = StatusCode>>(mut self, statuses: I) -> Self {
+        self.statuses = statuses.into_iter().collect();
+
+        self
+    }
+
+    /// Sets whether requests failing to connect or losing the connection are retried
+    pub fn connection_errors(mut self, retry: bool) -> Self {
+        self.connection_errors = retry;
+
+        self
+    }
+
+    /// Sets whether requests that timed out are retried
+    pub fn timeouts(mut self, retry: bool) -> Self {
+        self.timeouts = retry;
+
+        self
+    }
+
+    /// Sets whether the delay returned in the `Retry-After` header is used
+    /// instead of the backoff
+    pub fn respect_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+
+        self
+    }
+
+    /// Returns whether the request can be attempted again after the given attempt
+    pub(crate) fn allows_retry(&self, method: &Method, attempt: u32) -> bool {
+        attempt < self.max_attempts && is_idempotent(method)
+    }
+
+    /// Returns whether the error is worth retrying
+    pub(crate) fn is_retryable(&self, error: &ApiError) -> bool {
+        if let ApiError::Reqwest(e) = error {
+            if e.is_timeout() {
+                return self.timeouts;
+            }
+            if e.is_connect() || e.is_request() || e.is_body() {
+                return self.connection_errors;
+            }
+        }
+
+        error
+            .status()
+            .map(|status| self.statuses.contains(&status))
+            .unwrap_or(false)
+    }
+
+    /// Returns the delay before the next attempt or None if the api asks
+    /// to wait longer than the maximum backoff
+    pub(crate) fn delay(&self, attempt: u32, error: &ApiError) -> Option<Duration> {
+        if let Some(retry_after) = error.retry_after().filter(|_| self.respect_retry_after) {
+            return (retry_after <= self.max_backoff).then_some(retry_after);
+        }
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        // computed in seconds as the growing delay can exceed what a Duration can hold
+        let backoff = (self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent))
+            .min(self.max_backoff.as_secs_f64())
+            * (1.0 - self.jitter * fastrand::f64());
+
+        Some(Duration::try_from_secs_f64(backoff).unwrap_or(self.max_backoff))
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
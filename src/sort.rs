@@ -0,0 +1,322 @@
+use crate::includes::*;
+
+macro_rules! theme_sort {
+    (
+        pub enum $name: ident ($include: ident) {
+            $($variant: ident => $field_name: literal),*
+        }
+    ) => {
+        theme_sort!(@impl $name ($include::include_type()) {
+            $($variant => $field_name),*
+        });
+    };
+    (
+        pub enum $name: ident ($sort_type: literal) {
+            $($variant: ident => $field_name: literal),*
+        }
+    ) => {
+        theme_sort!(@impl $name ($sort_type) {
+            $($variant => $field_name),*
+        });
+    };
+    (
+        @impl $name: ident ($sort_type: expr) {
+            $($variant: ident => $field_name: literal),*
+        }
+    ) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            Id,
+            CreatedAt,
+            UpdatedAt,
+            DeletedAt,
+            $($variant),*
+        }
+
+        impl SortField for $name {
+            fn sort_type() -> &'static str {
+                $sort_type
+            }
+
+            fn field_name(&self) -> &'static str {
+                match self {
+                    Self::Id => "id",
+                    Self::CreatedAt => "created_at",
+                    Self::UpdatedAt => "updated_at",
+                    Self::DeletedAt => "deleted_at",
+                    $(Self::$variant => $field_name),*
+                }
+            }
+        }
+    }
+}
+
+pub trait SortField {
+    fn sort_type() -> &'static str;
+    fn field_name(&self) -> &'static str;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A sort specification with one or more keys applied in order
+///
+/// ```
+/// use animethemes_rs::sort::{AnimeSort, Sort};
+///
+/// // sort=-year,name
+/// let sort = Sort::desc(AnimeSort::Year).then_asc(AnimeSort::Name);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sort<F> {
+    keys: Vec<(F, SortDirection)>,
+    random: bool,
+}
+
+impl<F> Default for Sort<F> {
+    fn default() -> Self {
+        Self {
+            keys: Vec::new(),
+            random: false,
+        }
+    }
+}
+
+impl<F: SortField> Sort<F> {
+    /// Sorts ascending by the given field
+    pub fn asc(field: F) -> Self {
+        Self::default().then_asc(field)
+    }
+
+    /// Sorts descending by the given field
+    pub fn desc(field: F) -> Self {
+        Self::default().then_desc(field)
+    }
+
+    /// Returns the results in a random order.
+    /// Adding a sort key afterwards replaces the random order.
+    pub fn random() -> Self {
+        Self {
+            keys: Vec::new(),
+            random: true,
+        }
+    }
+
+    /// Adds an ascending sort key that is applied after the previous ones
+    pub fn then_asc(mut self, field: F) -> Self {
+        self.random = false;
+        self.keys.push((field, SortDirection::Ascending));
+
+        self
+    }
+
+    /// Adds a descending sort key that is applied after the previous ones
+    pub fn then_desc(mut self, field: F) -> Self {
+        self.random = false;
+        self.keys.push((field, SortDirection::Descending));
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.random && self.keys.is_empty()
+    }
+
+    /// Returns the value of the sort parameter like `-year,name`
+    pub fn value(&self) -> String {
+        if self.random {
+            return "random".to_string();
+        }
+
+        self.keys
+            .iter()
+            .map(|(field, direction)| match direction {
+                SortDirection::Ascending => field.field_name().to_string(),
+                SortDirection::Descending => format!("-{}", field.field_name()),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Returns the query parameters for the sort.
+    /// Scoped sorts are prefixed with the sort type like `sort[anime]`
+    /// which is required when sorting search results.
+    pub fn query(&self, scoped: bool) -> Vec<(String, String)> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let key = if scoped {
+            format!("sort[{}]", F::sort_type())
+        } else {
+            "sort".to_string()
+        };
+
+        vec![(key, self.value())]
+    }
+}
+
+theme_sort!(
+    pub enum AnnouncementSort ("announcement") {
+        Content => "content"
+    }
+);
+
+theme_sort!(
+    pub enum DumpSort ("dump") {
+        Path => "path"
+    }
+);
+
+theme_sort!(
+    pub enum FeaturedThemeSort (FeaturedThemeInclude) {
+        StartAt => "start_at",
+        EndAt => "end_at"
+    }
+);
+
+theme_sort!(
+    pub enum AnimeSort (AnimeInclude) {
+        Name => "name",
+        Slug => "slug",
+        Year => "year",
+        Season => "season"
+    }
+);
+
+theme_sort!(
+    pub enum SynonymSort (SynonymInclude) {
+        Text => "text"
+    }
+);
+
+theme_sort!(
+    pub enum AudioSort (AudioInclude) {
+        Basename => "basename",
+        Filename => "filename",
+        Path => "path",
+        Size => "size",
+        Mimetype => "mimetype"
+    }
+);
+
+theme_sort!(
+    pub enum ThemeSort (ThemeInclude) {
+        ThemeType => "type",
+        Sequence => "sequence",
+        Group => "group",
+        Slug => "slug"
+    }
+);
+
+theme_sort!(
+    pub enum ThemeEntrySort (ThemeEntryInclude) {
+        Version => "version",
+        Episodes => "episodes",
+        Nsfw => "nsfw",
+        Spoiler => "spoiler"
+    }
+);
+
+theme_sort!(
+    pub enum ArtistSort (ArtistInclude) {
+        Name => "name",
+        Slug => "slug"
+    }
+);
+
+theme_sort!(
+    pub enum ImageSort (ImageInclude) {
+        Path => "path",
+        Facet => "facet"
+    }
+);
+
+theme_sort!(
+    pub enum PlaylistSort (PlaylistInclude) {
+        Name => "name",
+        Visibility => "visibility",
+        TracksCount => "tracks_count"
+    }
+);
+
+theme_sort!(
+    pub enum PlaylistTrackSort (PlaylistTrackInclude) {
+    }
+);
+
+theme_sort!(
+    pub enum ResourceSort (ResourceInclude) {
+        Link => "link",
+        ExternalId => "external_id",
+        Site => "site"
+    }
+);
+
+theme_sort!(
+    pub enum SeriesSort (SeriesInclude) {
+        Name => "name",
+        Slug => "slug"
+    }
+);
+
+theme_sort!(
+    pub enum SongSort (SongInclude) {
+        Title => "title"
+    }
+);
+
+theme_sort!(
+    pub enum StudioSort (StudioInclude) {
+        Name => "name",
+        Slug => "slug"
+    }
+);
+
+theme_sort!(
+    pub enum VideoSort (VideoInclude) {
+        Basename => "basename",
+        Filename => "filename",
+        Path => "path",
+        Size => "size",
+        Resolution => "resolution",
+        NoCredits => "nc",
+        Subbed => "subbed",
+        Lyrics => "lyrics",
+        Uncensored => "uncen",
+        Source => "source",
+        Overlap => "overlap"
+    }
+);
+
+theme_sort!(
+    pub enum VideoScriptSort (VideoScriptInclude) {
+        Path => "path"
+    }
+);
+
+/// Sorts for the results of each type returned by a search
+#[derive(Clone, Default, Debug)]
+pub struct SearchSorts {
+    pub anime: Sort<AnimeSort>,
+    pub themes: Sort<ThemeSort>,
+    pub artists: Sort<ArtistSort>,
+    pub series: Sort<SeriesSort>,
+    pub songs: Sort<SongSort>,
+    pub videos: Sort<VideoSort>,
+}
+
+impl SearchSorts {
+    pub fn query(&self) -> Vec<(String, String)> {
+        let mut query = self.anime.query(true);
+        query.append(&mut self.themes.query(true));
+        query.append(&mut self.artists.query(true));
+        query.append(&mut self.series.query(true));
+        query.append(&mut self.songs.query(true));
+        query.append(&mut self.videos.query(true));
+
+        query
+    }
+}
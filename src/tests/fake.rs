@@ -0,0 +1,249 @@
+use crate::client::AnimeThemesClient;
+use crate::error::ApiResult;
+use crate::retry::RetryPolicy;
+use crate::transport::{response, Transport};
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Request, Response, StatusCode, Url};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+pub static FAKE_ENDPOINT: &str = "https://api.animethemes.test";
+
+/// A request received by the [FakeTransport]
+#[derive(Clone, Debug)]
+pub struct ReceivedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Value>,
+}
+
+impl ReceivedRequest {
+    /// Returns the value of a query parameter
+    pub fn query(&self, key: &str) -> Option<String> {
+        self.url
+            .query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    }
+}
+
+pub struct FakeResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl FakeResponse {
+    pub fn json(status: StatusCode, body: Value) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.insert(
+            HeaderName::from_static(name),
+            HeaderValue::from_str(value).unwrap(),
+        );
+
+        self
+    }
+}
+
+type Handler = Arc<dyn Fn(&ReceivedRequest) -> FakeResponse + Send + Sync>;
+
+struct Route {
+    path: String,
+    query: Vec<(String, String)>,
+    handler: Handler,
+}
+
+/// A transport serving canned responses by the path and query of the request
+#[derive(Clone, Default)]
+pub struct FakeTransport {
+    routes: Arc<Mutex<Vec<Route>>>,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl std::fmt::Debug for FakeTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeTransport").finish_non_exhaustive()
+    }
+}
+
+impl FakeTransport {
+    /// Responds to requests of the path with the given json
+    pub fn json(self, path: &str, body: Value) -> Self {
+        self.route(path, &[], move |_| {
+            FakeResponse::json(StatusCode::OK, body.clone())
+        })
+    }
+
+    /// Responds to requests of the path containing all query parameters with the given json
+    pub fn json_with_query(self, path: &str, query: &[(&str, &str)], body: Value) -> Self {
+        self.route(path, query, move |_| {
+            FakeResponse::json(StatusCode::OK, body.clone())
+        })
+    }
+
+    /// Responds to requests of the path with the given status and json
+    pub fn status(self, path: &str, status: StatusCode, body: Value) -> Self {
+        self.route(path, &[], move |_| FakeResponse::json(status, body.clone()))
+    }
+
+    /// Responds to requests of the path with the response returned by the handler
+    pub fn route<F>(self, path: &str, query: &[(&str, &str)], handler: F) -> Self
+    where
+        F: Fn(&ReceivedRequest) -> FakeResponse + Send + Sync + 'static,
+    {
+        self.routes.lock().unwrap().push(Route {
+            path: path.to_string(),
+            query: query
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            handler: Arc::new(handler),
+        });
+
+        self
+    }
+
+    /// Returns a client sending all requests to this transport
+    pub fn client(&self) -> AnimeThemesClient {
+        AnimeThemesClient::builder()
+            .api_endpoint(FAKE_ENDPOINT)
+            .transport(self.clone())
+            .retry(RetryPolicy::none())
+            .build()
+            .unwrap()
+    }
+
+    /// Returns all requests received so far
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the last request received
+    pub fn last_request(&self) -> ReceivedRequest {
+        self.requests.lock().unwrap().last().cloned().unwrap()
+    }
+
+    fn handle(&self, request: &ReceivedRequest) -> FakeResponse {
+        let routes = self.routes.lock().unwrap();
+        // the route with the most matching query parameters wins
+        let route = routes
+            .iter()
+            .filter(|route| route.path == request.url.path())
+            .filter(|route| {
+                route
+                    .query
+                    .iter()
+                    .all(|(k, v)| request.query(k).as_ref() == Some(v))
+            })
+            .max_by_key(|route| route.query.len());
+
+        match route {
+            Some(route) => (route.handler)(request),
+            None => FakeResponse::json(StatusCode::NOT_FOUND, json!({"message": "Not Found"})),
+        }
+    }
+}
+
+impl Transport for FakeTransport {
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>> {
+        let request = ReceivedRequest {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .and_then(|b| serde_json::from_slice(b).ok()),
+        };
+        let fake = self.handle(&request);
+        self.requests.lock().unwrap().push(request);
+
+        Box::pin(async move { Ok(response(fake.status, fake.headers, fake.body)) })
+    }
+}
+
+/// Builds the body of an index endpoint with links to the neighbouring pages of the path
+pub fn page(
+    path: &str,
+    key: &str,
+    data: Vec<Value>,
+    current_page: u32,
+    per_page: u32,
+    next: bool,
+) -> Value {
+    let link = |number: u32| format!("{FAKE_ENDPOINT}{path}?page[number]={number}");
+
+    json!({
+        key: data,
+        "links": {
+            "first": link(1),
+            "last": null,
+            "prev": (current_page > 1).then(|| link(current_page - 1)),
+            "next": next.then(|| link(current_page + 1)),
+        },
+        "meta": {
+            "current_page": current_page,
+            "per_page": per_page,
+            "from": (current_page - 1) * per_page + 1,
+            "to": current_page * per_page,
+            "path": format!("{FAKE_ENDPOINT}{path}"),
+        }
+    })
+}
+
+pub fn anime(id: u32, slug: &str, name: &str, year: u16, season: &str) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "slug": slug,
+        "year": year,
+        "season": season,
+        "synopsis": null,
+        "created_at": "2021-04-03T00:00:00.000000Z",
+        "updated_at": "2021-04-03T00:00:00.000000Z",
+        "deleted_at": null,
+    })
+}
+
+pub fn video(id: u32, basename: &str) -> Value {
+    let filename = basename.trim_end_matches(".webm");
+
+    json!({
+        "id": id,
+        "basename": basename,
+        "filename": filename,
+        "path": format!("2016/Fall/{basename}"),
+        "size": 43161298,
+        "mimetype": "video/webm",
+        "resolution": 1080,
+        "nc": true,
+        "subbed": false,
+        "lyrics": false,
+        "uncen": false,
+        "source": "BD",
+        "overlap": "None",
+        "tags": "NCBD1080",
+        "link": format!("https://v.animethemes.test/{basename}"),
+        "created_at": "2021-04-03T00:00:00.000000Z",
+        "updated_at": "2021-04-03T00:00:00.000000Z",
+        "deleted_at": null,
+    })
+}
+
+/// Merges the fields of the relations into an entry
+pub fn with(mut entry: Value, relations: Value) -> Value {
+    if let (Some(entry), Value::Object(relations)) = (entry.as_object_mut(), relations) {
+        entry.extend(relations);
+    }
+
+    entry
+}
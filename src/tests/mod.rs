@@ -1 +1,17 @@
+mod fake;
+#[cfg(feature = "blocking")]
+mod test_blocking;
+mod test_cache;
+mod test_catalogue;
 mod test_client;
+mod test_dump;
+mod test_fields;
+mod test_filters;
+#[cfg(feature = "mock-server")]
+mod test_mock_server;
+mod test_models;
+mod test_rate_limit;
+mod test_record;
+mod test_retry;
+mod test_search;
+mod test_sort;
@@ -0,0 +1,125 @@
+use crate::blocking::AnimeThemesClient;
+use crate::error::ApiError;
+use crate::fields::Fieldsets;
+use crate::filters::{AnimeFilter, SearchFilters};
+use crate::includes::{AnimeInclude, SearchIncludes};
+use crate::models::Dump;
+use crate::pagination::PaginateOptions;
+use crate::sort::{SearchSorts, Sort};
+use crate::tests::fake::*;
+use reqwest::StatusCode;
+use serde_json::json;
+
+fn client(fake: &FakeTransport) -> AnimeThemesClient {
+    AnimeThemesClient::with_client(fake.client()).unwrap()
+}
+
+#[test]
+fn it_blocks_until_the_response_is_received() {
+    let fake = FakeTransport::default()
+        .json(
+            "/anime/bakemonogatari",
+            json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+        )
+        .json(
+            "/search",
+            json!({"search": {"anime": [anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")]}}),
+        );
+    let client = client(&fake);
+    let anime = client
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .unwrap();
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+
+    let result = client
+        .search(
+            "bakemonogatari",
+            Fieldsets::default(),
+            SearchIncludes::default(),
+            SearchFilters::default(),
+            SearchSorts::default(),
+        )
+        .unwrap();
+    assert_eq!(result.anime.unwrap().len(), 1);
+    assert_eq!(fake.requests().len(), 2);
+}
+
+#[test]
+fn it_returns_errors() {
+    let fake = FakeTransport::default();
+    let result = client(&fake).anime("missing", AnimeInclude::default(), Fieldsets::default());
+
+    assert!(matches!(result, Err(ApiError::NotFound)));
+}
+
+#[test]
+fn it_iterates_over_pages() {
+    let fake = FakeTransport::default()
+        .json_with_query(
+            "/anime",
+            &[("page[number]", "1")],
+            page(
+                "/anime",
+                "anime",
+                vec![anime(1, "a", "A", 2020, "Fall")],
+                1,
+                1,
+                true,
+            ),
+        )
+        .json_with_query(
+            "/anime",
+            &[("page[number]", "2")],
+            page(
+                "/anime",
+                "anime",
+                vec![anime(2, "b", "B", 2021, "Spring")],
+                2,
+                1,
+                false,
+            ),
+        );
+    let client = client(&fake);
+    let anime: Vec<_> = client
+        .paginate(PaginateOptions::default().page_size(1), |client, page| {
+            client.list_anime(
+                page,
+                AnimeInclude::default(),
+                AnimeFilter::default(),
+                Sort::default(),
+                Fieldsets::default(),
+            )
+        })
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(anime.len(), 2);
+    assert_eq!(anime[1].slug.as_deref(), Some("b"));
+}
+
+#[test]
+fn it_downloads_dumps_into_writers() {
+    let content = "INSERT INTO `songs` (`song_id`, `title`) VALUES (1,'Redo');";
+    let fake = FakeTransport::default().route("/dump.sql", &[], move |_| FakeResponse {
+        status: StatusCode::OK,
+        headers: Default::default(),
+        body: content.to_string(),
+    });
+    let dump: Dump = serde_json::from_value(json!({
+        "id": 1,
+        "path": "dump.sql",
+        "link": "https://dump.animethemes.test/dump.sql",
+    }))
+    .unwrap();
+    let mut file = std::io::Cursor::new(Vec::new());
+    let size = client(&fake)
+        .download_dump(&dump, &mut file, |_| {})
+        .unwrap();
+
+    assert_eq!(size, content.len() as u64);
+    assert_eq!(file.into_inner(), content.as_bytes());
+}
@@ -0,0 +1,122 @@
+use crate::cache::{
+    CacheBackend, CachedResponse, DiskCache, MemoryCache, ResponseCache, TieredCache,
+};
+use std::time::Duration;
+
+fn response(body: &str) -> CachedResponse {
+    CachedResponse::new(body.to_string(), Some("\"abc\"".to_string()), None)
+}
+
+#[test]
+fn it_evicts_the_least_recently_used_response() {
+    let cache = MemoryCache::new(2);
+    cache.put("a", response("a"));
+    cache.put("b", response("b"));
+    cache.get("a");
+    cache.put("c", response("c"));
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("a").is_some());
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("c").is_some());
+}
+
+#[test]
+fn it_stores_responses_on_disk() {
+    let directory = std::env::temp_dir().join(format!("animethemes-cache-{}", std::process::id()));
+    let cache = DiskCache::new(&directory);
+    cache.put("/anime/bakemonogatari?", response("{}"));
+
+    let cached = cache.get("/anime/bakemonogatari?").unwrap();
+    assert_eq!(cached.body, "{}");
+    assert_eq!(cached.etag.as_deref(), Some("\"abc\""));
+    assert!(cache.get("/anime/kimi_no_na_wa?").is_none());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn it_builds_keys_independent_of_the_query_order() {
+    let a = ResponseCache::key(
+        "/anime",
+        &[
+            ("include".to_string(), "animethemes".to_string()),
+            ("page[size]".to_string(), "10".to_string()),
+        ],
+        None,
+    );
+    let b = ResponseCache::key(
+        "/anime",
+        &[
+            ("page[size]".to_string(), "10".to_string()),
+            ("include".to_string(), "animethemes".to_string()),
+        ],
+        None,
+    );
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_builds_keys_per_token() {
+    let anonymous = ResponseCache::key("/me/playlist", &[], None);
+    let a = ResponseCache::key("/me/playlist", &[], Some("token-a"));
+    let b = ResponseCache::key("/me/playlist", &[], Some("token-b"));
+
+    assert_ne!(anonymous, a);
+    assert_ne!(a, b);
+    assert!(!a.contains("token-a"));
+}
+
+#[test]
+fn it_builds_keys_per_endpoint() {
+    let a = ResponseCache::key("https://api.animethemes.moe/anime", &[], None);
+    let b = ResponseCache::key("https://staging.animethemes.moe/anime", &[], None);
+
+    assert_ne!(a, b);
+}
+
+#[test]
+fn it_removes_responses_by_prefix() {
+    let directory = std::env::temp_dir().join("animethemes-rs-test-cache-prefix");
+    let _ = std::fs::remove_dir_all(&directory);
+    let cache = TieredCache::new(MemoryCache::new(10), DiskCache::new(&directory));
+    cache.put("/playlist?", response("playlists"));
+    cache.put("/playlist/aBc1/track?", response("tracks"));
+    cache.put("/anime?", response("anime"));
+    cache.remove_prefix("/playlist");
+
+    assert!(cache.get("/playlist?").is_none());
+    assert!(cache.get("/playlist/aBc1/track?").is_none());
+    assert_eq!(cache.get("/anime?").unwrap().body, "anime");
+    assert!(DiskCache::new(&directory).get("/playlist?").is_none());
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn it_uses_the_longest_matching_endpoint_ttl() {
+    let cache = ResponseCache::memory(10)
+        .ttl(Duration::from_secs(1))
+        .endpoint_ttl("/anime", Duration::from_secs(2))
+        .endpoint_ttl("/animeyear", Duration::from_secs(3));
+
+    assert_eq!(cache.ttl_for("/video"), Duration::from_secs(1));
+    assert_eq!(
+        cache.ttl_for("/anime/bakemonogatari"),
+        Duration::from_secs(2)
+    );
+    assert_eq!(cache.ttl_for("/animeyear/2009"), Duration::from_secs(3));
+}
+
+#[test]
+fn it_matches_endpoint_ttls_by_whole_segments() {
+    let cache = ResponseCache::memory(10)
+        .ttl(Duration::from_secs(1))
+        .endpoint_ttl("/anime", Duration::from_secs(2));
+
+    assert_eq!(cache.ttl_for("/anime"), Duration::from_secs(2));
+    assert_eq!(cache.ttl_for("/anime?q=a"), Duration::from_secs(2));
+    assert_eq!(cache.ttl_for("/animetheme"), Duration::from_secs(1));
+    assert_eq!(cache.ttl_for("/animeyear/2009"), Duration::from_secs(1));
+}
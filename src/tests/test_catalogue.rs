@@ -0,0 +1,149 @@
+use crate::catalogue::Catalogue;
+use crate::client::DEFAULT_VIDEO_ENDPOINT;
+use crate::dump::DumpData;
+use crate::models::{Anime, Artist, Theme, ThemeEntry};
+use crate::tests::test_dump::DUMP;
+
+#[test]
+fn it_deduplicates_nested_entries() {
+    let themes: Vec<Theme> = serde_json::from_str(
+        r#"[
+            {
+                "id": 10,
+                "slug": "OP1",
+                "anime": {"id": 1, "name": "Bakemonogatari", "slug": "bakemonogatari"},
+                "song": {
+                    "id": 20,
+                    "title": "Staple Stable",
+                    "artists": [{"id": 50, "name": "Chiwa Saito", "slug": "chiwa_saito", "as": "Hitagi Senjougahara"}]
+                },
+                "animethemeentries": [
+                    {"id": 30, "videos": [{"id": 40, "basename": "Bakemonogatari-OP1.webm"}]}
+                ]
+            },
+            {
+                "id": 11,
+                "slug": "ED1",
+                "anime": {"id": 1, "name": "Bakemonogatari", "slug": "bakemonogatari"}
+            }
+        ]"#,
+    )
+    .unwrap();
+    let mut catalogue = Catalogue::new();
+    themes.into_iter().for_each(|t| catalogue.insert_theme(t));
+
+    assert_eq!(catalogue.all_anime().count(), 1);
+    assert_eq!(catalogue.themes_of(1).len(), 2);
+    assert!(catalogue.theme(10).unwrap().anime.is_none());
+    assert_eq!(
+        catalogue.anime_of_theme(11).unwrap().name.as_deref(),
+        Some("Bakemonogatari")
+    );
+    assert_eq!(
+        catalogue.song_of(10).unwrap().title.as_deref(),
+        Some("Staple Stable")
+    );
+    assert_eq!(catalogue.songs_of(50).len(), 1);
+    assert_eq!(
+        catalogue.credited_character(20, 50),
+        Some("Hitagi Senjougahara")
+    );
+    assert_eq!(
+        catalogue.anime_of_video("Bakemonogatari-OP1.webm")[0]
+            .slug
+            .as_deref(),
+        Some("bakemonogatari")
+    );
+}
+
+#[test]
+fn it_navigates_dump_relations() {
+    let catalogue =
+        Catalogue::from(DumpData::from_sql(DUMP.as_bytes(), DEFAULT_VIDEO_ENDPOINT).unwrap());
+
+    assert_eq!(catalogue.themes_of(1).len(), 1);
+    assert_eq!(catalogue.entries_of(10).len(), 1);
+    assert_eq!(
+        catalogue.videos_of(30)[0].basename.as_deref(),
+        Some("Bakemonogatari-OP1.webm")
+    );
+    assert_eq!(
+        catalogue.artists_of(20)[0].name.as_deref(),
+        Some("Chiwa Saito")
+    );
+    assert_eq!(
+        catalogue.credited_character(20, 50),
+        Some("Hitagi Senjougahara")
+    );
+    assert_eq!(
+        catalogue.anime_of_video("Bakemonogatari-OP1.webm")[0]
+            .meta
+            .id,
+        Some(1)
+    );
+    assert!(catalogue.anime_of_video("unknown.webm").is_empty());
+}
+
+#[test]
+fn it_merges_sparse_copies_into_stored_entries() {
+    let mut catalogue = Catalogue::new();
+    catalogue.insert_anime(
+        serde_json::from_str::<Anime>(
+            r#"{"id": 1, "name": "Bakemonogatari", "slug": "bakemonogatari", "year": 2009, "synopsis": "Oddities"}"#,
+        )
+        .unwrap(),
+    );
+    catalogue.insert_theme(
+        serde_json::from_str::<Theme>(
+            r#"{"id": 10, "slug": "OP1", "anime": {"id": 1, "name": "Bakemonogatari (TV)"}}"#,
+        )
+        .unwrap(),
+    );
+    let anime = catalogue.anime(1).unwrap();
+
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari (TV)"));
+    assert_eq!(anime.slug.as_deref(), Some("bakemonogatari"));
+    assert_eq!(anime.year, Some(2009));
+    assert_eq!(anime.synopsis.as_deref(), Some("Oddities"));
+    assert_eq!(catalogue.themes_of(1).len(), 1);
+}
+
+#[test]
+fn it_prefers_fresh_flags_over_stored_ones() {
+    let mut catalogue = Catalogue::new();
+    let entries: Vec<ThemeEntry> = serde_json::from_str(
+        r#"[{"id": 1, "spoiler": true, "nsfw": true}, {"id": 1, "spoiler": false}]"#,
+    )
+    .unwrap();
+    entries.into_iter().for_each(|e| catalogue.insert_entry(e));
+    let entry = catalogue.entry(1).unwrap();
+
+    assert_eq!(entry.spoiler, Some(false));
+    assert_eq!(entry.nsfw, Some(true));
+}
+
+#[test]
+fn it_strips_the_membership_of_artists() {
+    let mut catalogue = Catalogue::new();
+    catalogue.insert_artist(
+        serde_json::from_str::<Artist>(
+            r#"{"id": 2, "name": "Karen", "artistmember": {"alias": "Karen-chan"}}"#,
+        )
+        .unwrap(),
+    );
+    let artist = catalogue.artist(2).unwrap();
+
+    assert!(artist.membership.is_none());
+    assert_eq!(artist.credited_name(), Some("Karen"));
+}
+
+#[test]
+fn it_skips_entries_without_id() {
+    let mut catalogue = Catalogue::new();
+    let themes: Vec<Theme> =
+        serde_json::from_str(r#"[{"slug": "OP1", "anime": {"id": 1}}, {"slug": "ED1"}]"#).unwrap();
+    themes.into_iter().for_each(|t| catalogue.insert_theme(t));
+
+    assert_eq!(catalogue.all_themes().count(), 0);
+    assert!(catalogue.themes_of(1).is_empty());
+}
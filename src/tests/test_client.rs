@@ -1,5 +1,21 @@
-use crate::client::AnimeThemesClient;
+use crate::cache::ResponseCache;
+use crate::client::{AnimeThemesClient, DEFAULT_USER_AGENT};
+use crate::error::{ApiError, ApiResult};
+use crate::fields::*;
+use crate::filters::*;
 use crate::includes::*;
+use crate::models::{
+    AnimeSeason, Dump, NewPlaylist, PlaylistUpdate, PlaylistVisibility, SearchResponse,
+};
+use crate::pagination::{PaginateOptions, Pagination};
+use crate::retry::RetryPolicy;
+use crate::sort::*;
+use crate::tests::fake::*;
+use crate::transport::{Recorder, Replayer};
+use futures::StreamExt;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, IF_NONE_MATCH, USER_AGENT};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
 
 const TEST_QUERIES: &[&str] = &[
     "vivy",
@@ -17,13 +33,32 @@ const TEST_QUERIES: &[&str] = &[
     "kaguya",
 ];
 
+fn search_fixture() -> Value {
+    serde_json::from_str(include_str!("fixtures/search.json")).unwrap()
+}
+
+fn vivy() -> Value {
+    anime(
+        1929,
+        "vivy_fluorite_eyes_song",
+        "Vivy: Fluorite Eye's Song",
+        2021,
+        "Spring",
+    )
+}
+
+fn kimiuso_op2() -> Value {
+    video(7393, "KimiUso-OP2.webm")
+}
+
 #[tokio::test]
 async fn it_searches() {
-    let client = AnimeThemesClient::default();
-    let result = client
+    let fake = FakeTransport::default().json("/search", search_fixture());
+    let result = fake
+        .client()
         .search(
             "re:zero",
-            &[],
+            Fieldsets::default(),
             SearchIncludes {
                 anime: AnimeInclude::default()
                     .themes()
@@ -31,9 +66,18 @@ async fn it_searches() {
                     .themes_entries_videos(),
                 ..Default::default()
             },
+            SearchFilters::default(),
+            SearchSorts::default(),
         )
         .await
         .unwrap();
+    let request = fake.last_request();
+
+    assert_eq!(request.query("q").as_deref(), Some("re:zero"));
+    assert_eq!(
+        request.query("include[anime]").as_deref(),
+        Some("animethemes,animethemes.animethemeentries,animethemes.animethemeentries.videos")
+    );
     assert!(result.artists.is_some());
     assert!(result.songs.is_some());
     assert!(result.anime.is_some());
@@ -42,16 +86,34 @@ async fn it_searches() {
     assert!(result.videos.is_some());
 }
 
+static SEARCH_FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/search");
+
+async fn search_all(client: &AnimeThemesClient, query: &str) -> ApiResult<SearchResponse> {
+    client
+        .search(
+            query,
+            Fieldsets::default(),
+            SearchIncludes::all(),
+            SearchFilters::default(),
+            SearchSorts::default(),
+        )
+        .await
+}
+
 #[tokio::test]
 async fn all_models_are_correct() {
-    let client = AnimeThemesClient::default();
+    let replayer = Replayer::load(SEARCH_FIXTURES).unwrap();
+    assert_eq!(replayer.len(), TEST_QUERIES.len());
+
+    let client = AnimeThemesClient::builder()
+        .transport(replayer)
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap();
 
     for query in TEST_QUERIES {
         println!("testing query '{query}'");
-        let result = client
-            .search(query, &[], SearchIncludes::all())
-            .await
-            .unwrap();
+        let result = search_all(&client, query).await.unwrap();
         assert!(result.artists.is_some());
         assert!(result.songs.is_some());
         assert!(result.anime.is_some());
@@ -61,22 +123,56 @@ async fn all_models_are_correct() {
     }
 }
 
+/// Updates the fixtures used by [all_models_are_correct] with the responses of the api
+#[tokio::test]
+#[ignore = "requires network access"]
+async fn record_search_fixtures() {
+    let client = AnimeThemesClient::builder()
+        .transport(Recorder::new(reqwest::Client::new(), SEARCH_FIXTURES))
+        .build()
+        .unwrap();
+
+    for query in TEST_QUERIES {
+        search_all(&client, query).await.unwrap();
+    }
+}
+
 #[tokio::test]
 async fn it_returns_anime_by_slug() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .anime("vivy_fluorite_eyes_song", AnimeInclude::default().themes())
+    let fake = FakeTransport::default().json(
+        "/anime/vivy_fluorite_eyes_song",
+        json!({"anime": with(vivy(), json!({"animethemes": [{"id": 1, "type": "OP", "sequence": 1, "slug": "OP1"}]}))}),
+    );
+    let result = fake
+        .client()
+        .anime(
+            "vivy_fluorite_eyes_song",
+            AnimeInclude::default().themes(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
+    assert_eq!(
+        fake.last_request().query("include").as_deref(),
+        Some("animethemes")
+    );
     assert!(result.themes.is_some());
 }
 
 #[tokio::test]
 async fn it_returns_artists_by_slug() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .artist("lisa", ArtistInclude::default().songs())
+    let fake = FakeTransport::default().json(
+        "/artist/lisa",
+        json!({"artist": {"id": 1, "name": "LiSA", "slug": "lisa", "songs": [{"id": 2, "title": "crossing field"}]}}),
+    );
+    let result = fake
+        .client()
+        .artist(
+            "lisa",
+            ArtistInclude::default().songs(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -85,9 +181,25 @@ async fn it_returns_artists_by_slug() {
 
 #[tokio::test]
 async fn it_returns_entries_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .entry(11948, ThemeEntryInclude::default().theme().videos())
+    let fake = FakeTransport::default().json(
+        "/animethemeentry/11948",
+        json!({"animethemeentry": {
+            "id": 11948,
+            "version": null,
+            "episodes": "2-13",
+            "nsfw": false,
+            "spoiler": false,
+            "animetheme": {"id": 8187, "type": "OP", "sequence": 1, "slug": "OP1"},
+            "videos": [video(11215, "ReZero-OP1.webm")]
+        }}),
+    );
+    let result = fake
+        .client()
+        .entry(
+            11948,
+            ThemeEntryInclude::default().theme().videos(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -97,9 +209,13 @@ async fn it_returns_entries_by_id() {
 
 #[tokio::test]
 async fn it_returns_images_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .image(7247, ImageInclude::default().anime())
+    let fake = FakeTransport::default().json(
+        "/image/7247",
+        json!({"image": {"id": 7247, "path": "anime/vivy.jpg", "facet": "Large Cover", "anime": [vivy()]}}),
+    );
+    let result = fake
+        .client()
+        .image(7247, ImageInclude::default().anime(), Fieldsets::default())
         .await
         .unwrap();
 
@@ -108,9 +224,17 @@ async fn it_returns_images_by_id() {
 
 #[tokio::test]
 async fn it_returns_resources_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .resource(3588, ResourceInclude::default().anime())
+    let fake = FakeTransport::default().json(
+        "/resource/3588",
+        json!({"resource": {"id": 3588, "link": "https://myanimelist.net/anime/46095/", "external_id": 46095, "site": "MyAnimeList", "anime": [vivy()]}}),
+    );
+    let result = fake
+        .client()
+        .resource(
+            3588,
+            ResourceInclude::default().anime(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -119,9 +243,17 @@ async fn it_returns_resources_by_id() {
 
 #[tokio::test]
 async fn it_returns_series_by_slug() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .series("shingeki_no_kyojin", SeriesInclude::default().anime())
+    let fake = FakeTransport::default().json(
+        "/series/shingeki_no_kyojin",
+        json!({"series": {"id": 1, "name": "Shingeki no Kyojin", "slug": "shingeki_no_kyojin", "anime": [anime(2, "shingeki_no_kyojin", "Shingeki no Kyojin", 2013, "Spring")]}}),
+    );
+    let result = fake
+        .client()
+        .series(
+            "shingeki_no_kyojin",
+            SeriesInclude::default().anime(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -130,9 +262,17 @@ async fn it_returns_series_by_slug() {
 
 #[tokio::test]
 async fn it_returns_synonyms_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .synonym(2462, SynonymInclude::default().anime())
+    let fake = FakeTransport::default().json(
+        "/animesynonym/2462",
+        json!({"animesynonym": {"id": 2462, "text": "Vivy", "anime": vivy()}}),
+    );
+    let result = fake
+        .client()
+        .synonym(
+            2462,
+            SynonymInclude::default().anime(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -141,9 +281,13 @@ async fn it_returns_synonyms_by_id() {
 
 #[tokio::test]
 async fn it_returns_songs_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .song(8188, SongInclude::default().themes())
+    let fake = FakeTransport::default().json(
+        "/song/8188",
+        json!({"song": {"id": 8188, "title": "Redo", "animethemes": [{"id": 8187, "type": "OP", "sequence": 1, "slug": "OP1"}]}}),
+    );
+    let result = fake
+        .client()
+        .song(8188, SongInclude::default().themes(), Fieldsets::default())
         .await
         .unwrap();
 
@@ -152,9 +296,17 @@ async fn it_returns_songs_by_id() {
 
 #[tokio::test]
 async fn it_returns_themes_by_id() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .theme(8187, ThemeInclude::default().entries())
+    let fake = FakeTransport::default().json(
+        "/animetheme/8187",
+        json!({"animetheme": {"id": 8187, "type": "OP", "sequence": 1, "slug": "OP1", "animethemeentries": [{"id": 11948, "nsfw": false, "spoiler": false}]}}),
+    );
+    let result = fake
+        .client()
+        .theme(
+            8187,
+            ThemeInclude::default().entries(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
@@ -163,11 +315,1072 @@ async fn it_returns_themes_by_id() {
 
 #[tokio::test]
 async fn it_returns_videos_by_basename() {
-    let client = AnimeThemesClient::default();
-    let result = client
-        .video("KimiUso-OP2.webm", VideoInclude::default().entries())
+    let fake = FakeTransport::default().json(
+        "/video/KimiUso-OP2.webm",
+        json!({"video": with(kimiuso_op2(), json!({"animethemeentries": [{"id": 1, "nsfw": false, "spoiler": false}]}))}),
+    );
+    let result = fake
+        .client()
+        .video(
+            "KimiUso-OP2.webm",
+            VideoInclude::default().entries(),
+            Fieldsets::default(),
+        )
         .await
         .unwrap();
 
     assert!(result.entries.is_some())
 }
+
+#[tokio::test]
+async fn it_returns_not_found_for_unknown_slugs() {
+    let fake = FakeTransport::default();
+    let result = fake
+        .client()
+        .anime(
+            "this_anime_does_not_exist",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await;
+
+    assert!(matches!(result, Err(ApiError::NotFound)))
+}
+
+#[tokio::test]
+async fn it_returns_server_errors() {
+    let fake = FakeTransport::default().status(
+        "/anime/bakemonogatari",
+        StatusCode::SERVICE_UNAVAILABLE,
+        json!({"message": "Service Unavailable"}),
+    );
+    let result = fake
+        .client()
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ApiError::Server {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            ..
+        })
+    ));
+    assert_eq!(fake.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn it_lists_anime() {
+    let data = (6..=10)
+        .map(|id| anime(id, &format!("anime_{id}"), "Anime", 2021, "Spring"))
+        .collect();
+    let fake = FakeTransport::default().json_with_query(
+        "/anime",
+        &[("page[size]", "5"), ("page[number]", "2")],
+        page("/anime", "anime", data, 2, 5, true),
+    );
+    let page = fake
+        .client()
+        .list_anime(
+            Pagination::new(5, 2),
+            AnimeInclude::default(),
+            AnimeFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(page.meta.current_page, 2);
+    assert_eq!(page.meta.per_page, 5);
+    assert_eq!(page.data.len(), 5);
+    assert!(page.has_next());
+}
+
+#[tokio::test]
+async fn it_lists_videos() {
+    let data = (1..=3)
+        .map(|id| video(id, &format!("Video-OP{id}.webm")))
+        .collect();
+    let fake = FakeTransport::default().json("/video", page("/video", "videos", data, 1, 3, true));
+    let page = fake
+        .client()
+        .list_videos(
+            Pagination::default().size(3),
+            VideoInclude::default(),
+            VideoFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fake.last_request().query("page[size]").as_deref(),
+        Some("3")
+    );
+    assert_eq!(page.data.len(), 3);
+    assert!(page.links.prev.is_none());
+}
+
+#[tokio::test]
+async fn it_streams_videos() {
+    let mut fake = FakeTransport::default();
+
+    for number in 1..=4u32 {
+        let data = (1..=3)
+            .map(|i| video(number * 3 + i, &format!("Video-{number}-{i}.webm")))
+            .collect();
+        fake = fake.json_with_query(
+            "/video",
+            &[("page[number]", &number.to_string())],
+            page("/video", "videos", data, number, 3, number < 4),
+        );
+    }
+    let options = PaginateOptions::default()
+        .page_size(3)
+        .concurrency(2)
+        .max_items(7);
+    let videos: Vec<_> = fake
+        .client()
+        .paginate(options, |client, page| {
+            client.list_videos(
+                page,
+                VideoInclude::default(),
+                VideoFilter::default(),
+                Sort::default(),
+                Fieldsets::default(),
+            )
+        })
+        .collect()
+        .await;
+
+    assert_eq!(videos.len(), 7);
+    assert!(videos.iter().all(|v| v.is_ok()));
+}
+
+#[tokio::test]
+async fn it_follows_the_next_links() {
+    let mut second = page(
+        "/anime",
+        "anime",
+        vec![anime(2, "b", "B", 2021, "Spring")],
+        2,
+        50,
+        false,
+    );
+    second["links"]["prev"] = Value::Null;
+    let mut first = page(
+        "/anime",
+        "anime",
+        vec![anime(1, "a", "A", 2020, "Fall")],
+        1,
+        1,
+        true,
+    );
+    first["links"]["next"] =
+        format!("{FAKE_ENDPOINT}/anime?filter%5Byear%5D=2020&page%5Bsize%5D=50&page%5Bnumber%5D=2")
+            .into();
+    let fake = FakeTransport::default()
+        .json_with_query("/anime", &[("page[number]", "1")], first)
+        .json_with_query("/anime", &[("page[number]", "2")], second);
+    let anime: Vec<_> = fake
+        .client()
+        .paginate(PaginateOptions::default().page_size(1), |client, page| {
+            client.list_anime(
+                page,
+                AnimeInclude::default(),
+                AnimeFilter::default().year(2020),
+                Sort::default(),
+                Fieldsets::default(),
+            )
+        })
+        .collect()
+        .await;
+    let request = fake.last_request();
+
+    assert_eq!(anime.len(), 2);
+    assert_eq!(anime[1].as_ref().unwrap().slug.as_deref(), Some("b"));
+    assert_eq!(fake.requests().len(), 2);
+    assert_eq!(request.query("page[size]").as_deref(), Some("50"));
+    assert_eq!(request.query("filter[year]").as_deref(), Some("2020"));
+}
+
+#[tokio::test]
+async fn it_filters_anime() {
+    let fake = FakeTransport::default().json(
+        "/anime",
+        page("/anime", "anime", vec![vivy()], 1, 15, false),
+    );
+    fake.client()
+        .list_anime(
+            Pagination::default(),
+            AnimeInclude::default(),
+            AnimeFilter::default()
+                .year(2021)
+                .season(AnimeSeason::Spring),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let request = fake.last_request();
+
+    assert_eq!(request.query("filter[year]").as_deref(), Some("2021"));
+    assert_eq!(request.query("filter[season]").as_deref(), Some("Spring"));
+}
+
+#[tokio::test]
+async fn it_sorts_anime() {
+    let data = vec![
+        anime(1, "b", "B", 2021, "Spring"),
+        anime(2, "a", "A", 2020, "Fall"),
+    ];
+    let fake = FakeTransport::default().json("/anime", page("/anime", "anime", data, 1, 20, false));
+    fake.client()
+        .list_anime(
+            Pagination::default().size(20),
+            AnimeInclude::default(),
+            AnimeFilter::default(),
+            Sort::desc(AnimeSort::Year).then_asc(AnimeSort::Name),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fake.last_request().query("sort").as_deref(),
+        Some("-year,name")
+    );
+}
+
+#[tokio::test]
+async fn it_returns_sparse_fieldsets() {
+    let data = vec![
+        json!({"basename": "KimiUso-OP2.webm", "link": "https://v.animethemes.test/KimiUso-OP2.webm", "resolution": 1080}),
+    ];
+    let fake = FakeTransport::default().json("/video", page("/video", "videos", data, 1, 5, false));
+    let page = fake
+        .client()
+        .list_videos(
+            Pagination::default().size(5),
+            VideoInclude::default(),
+            VideoFilter::default(),
+            Sort::default(),
+            Fieldsets {
+                videos: VideoFields::default().basename().link().resolution(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fake.last_request().query("fields[video]").as_deref(),
+        Some("basename,resolution,link")
+    );
+    assert!(page
+        .data
+        .iter()
+        .all(|video| video.basename.is_some() && video.path.is_none()));
+}
+
+#[tokio::test]
+async fn it_returns_studios_by_slug() {
+    let fake = FakeTransport::default().json(
+        "/studio/kyoto_animation",
+        json!({"studio": {"id": 1, "name": "Kyoto Animation", "slug": "kyoto_animation", "anime": [anime(2, "k_on", "K-On!", 2009, "Spring")]}}),
+    );
+    let result = fake
+        .client()
+        .studio(
+            "kyoto_animation",
+            StudioInclude::default().anime(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.anime.is_some())
+}
+
+#[tokio::test]
+async fn it_includes_studios_of_anime() {
+    let fake = FakeTransport::default().json(
+        "/anime/vivy_fluorite_eyes_song",
+        json!({"anime": with(vivy(), json!({"studios": [{"id": 3, "name": "Wit Studio", "slug": "wit_studio"}]}))}),
+    );
+    let result = fake
+        .client()
+        .anime(
+            "vivy_fluorite_eyes_song",
+            AnimeInclude::default().studios(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.studios.is_some())
+}
+
+#[tokio::test]
+async fn it_returns_artist_members() {
+    let fake = FakeTransport::default().json(
+        "/artist/claris",
+        json!({"artist": {"id": 2, "name": "ClariS", "slug": "claris", "members": [
+            {"id": 1, "name": "Clara", "slug": "clara", "artistmember": {"alias": null, "as": null}}
+        ]}}),
+    );
+    let result = fake
+        .client()
+        .artist(
+            "claris",
+            ArtistInclude::default().members(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.group_members().count() > 0)
+}
+
+#[tokio::test]
+async fn it_returns_audio_by_basename() {
+    let fake = FakeTransport::default().json(
+        "/audio/KimiUso-OP2.ogg",
+        json!({"audio": {"id": 1, "basename": "KimiUso-OP2.ogg", "filename": "KimiUso-OP2", "size": 3141592, "mimetype": "audio/ogg", "videos": [kimiuso_op2()]}}),
+    );
+    let result = fake
+        .client()
+        .audio(
+            "KimiUso-OP2.ogg",
+            AudioInclude::default().videos(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.videos.is_some())
+}
+
+#[tokio::test]
+async fn it_includes_audio_of_videos() {
+    let fake = FakeTransport::default().json(
+        "/video/KimiUso-OP2.webm",
+        json!({"video": with(kimiuso_op2(), json!({"audio": {"id": 1, "basename": "KimiUso-OP2.ogg"}}))}),
+    );
+    let result = fake
+        .client()
+        .video(
+            "KimiUso-OP2.webm",
+            VideoInclude::default().audio(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.audio.is_some())
+}
+
+#[tokio::test]
+async fn it_downloads_video_scripts() {
+    let fake = FakeTransport::default()
+        .json(
+            "/video/KimiUso-OP2.webm",
+            json!({"video": with(kimiuso_op2(), json!({"videoscript": {
+                "id": 1,
+                "path": "2016/Fall/KimiUso-OP2.txt",
+                "link": "https://script.animethemes.test/KimiUso-OP2.txt"
+            }}))}),
+        )
+        .route("/KimiUso-OP2.txt", &[], |_| FakeResponse {
+            status: StatusCode::OK,
+            headers: Default::default(),
+            body: "ffmpeg -i KimiUso-OP2.mkv KimiUso-OP2.webm".to_string(),
+        });
+    let client = fake.client();
+    let video = client
+        .video(
+            "KimiUso-OP2.webm",
+            VideoInclude::default().script(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let script = video.script.unwrap();
+    let text = client.download_video_script(&script).await.unwrap();
+
+    assert!(text.contains("ffmpeg"))
+}
+
+#[tokio::test]
+async fn it_lists_playlists() {
+    let track = |id: &str, previous: Option<&str>, next: Option<&str>| {
+        json!({
+            "id": id,
+            "previous": previous.map(|id| json!({"id": id})),
+            "next": next.map(|id| json!({"id": id})),
+            "video": kimiuso_op2(),
+        })
+    };
+    let fake = FakeTransport::default()
+        .json(
+            "/playlist",
+            page(
+                "/playlist",
+                "playlists",
+                vec![json!({"id": "aBc1", "name": "Favorites", "visibility": "Public"})],
+                1,
+                1,
+                false,
+            ),
+        )
+        .json(
+            "/playlist/aBc1/track",
+            page(
+                "/playlist/aBc1/track",
+                "tracks",
+                vec![track("t2", Some("t1"), None), track("t1", None, Some("t2"))],
+                1,
+                15,
+                false,
+            ),
+        );
+    let client = fake.client();
+    let page = client
+        .list_playlists(
+            Pagination::default().size(1),
+            PlaylistInclude::default(),
+            PlaylistFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let playlist = page.data.first().unwrap();
+    let tracks = client
+        .playlist_tracks_in_order(
+            playlist.meta.id.as_deref().unwrap(),
+            PlaylistTrackInclude::default().video(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let ids: Vec<&str> = tracks.iter().filter_map(|t| t.meta.id.as_deref()).collect();
+
+    assert_eq!(ids, vec!["t1", "t2"]);
+    assert!(tracks.iter().all(|track| track.video.is_some()))
+}
+
+/// Responds like the api to requests that require authentication
+fn requires_token(body: Value) -> impl Fn(&ReceivedRequest) -> FakeResponse {
+    move |request| match request.headers.get(AUTHORIZATION) {
+        Some(value) if value == "Bearer secret-token" => {
+            FakeResponse::json(StatusCode::CREATED, body.clone())
+        }
+        _ => FakeResponse::json(
+            StatusCode::UNAUTHORIZED,
+            json!({"message": "Unauthenticated."}),
+        ),
+    }
+}
+
+#[tokio::test]
+async fn it_requires_a_token_for_write_endpoints() {
+    let fake = FakeTransport::default().route(
+        "/playlist",
+        &[],
+        requires_token(
+            json!({"playlist": {"id": "aBc1", "name": "test", "visibility": "Private"}}),
+        ),
+    );
+    let playlist = NewPlaylist {
+        name: "test".to_string(),
+        visibility: PlaylistVisibility::Private,
+        description: None,
+    };
+    let result = fake.client().create_playlist(&playlist).await;
+
+    assert!(matches!(result, Err(ApiError::Unauthorized { .. })));
+
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .token("secret-token")
+        .build()
+        .unwrap();
+    let created = client.create_playlist(&playlist).await.unwrap();
+    let request = fake.last_request();
+
+    assert_eq!(request.method, Method::POST);
+    assert_eq!(request.body.unwrap()["name"], "test");
+    assert_eq!(created.meta.id.as_deref(), Some("aBc1"));
+}
+
+#[tokio::test]
+async fn it_sends_requests_as_configured_by_the_builder() {
+    let fake = FakeTransport::default().route(
+        "/api/anime/bakemonogatari",
+        &[],
+        requires_token(
+            json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+        ),
+    );
+    let client = AnimeThemesClient::builder()
+        .api_endpoint("https://mirror.animethemes.test/api")
+        .token("secret-token")
+        .user_agent("my-service/1.0")
+        .default_header(
+            HeaderName::from_static("x-client"),
+            HeaderValue::from_static("tests"),
+        )
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+    let anime = client
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let request = fake.last_request();
+
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+    assert_eq!(
+        request.url.as_str(),
+        "https://mirror.animethemes.test/api/anime/bakemonogatari"
+    );
+    assert_eq!(request.headers[AUTHORIZATION], "Bearer secret-token");
+    assert_eq!(request.headers[USER_AGENT], "my-service/1.0");
+    assert_eq!(request.headers["x-client"], "tests");
+}
+
+#[tokio::test]
+async fn it_sends_the_default_user_agent() {
+    let fake = FakeTransport::default();
+    let _ = fake
+        .client()
+        .anime("missing", AnimeInclude::default(), Fieldsets::default())
+        .await;
+    let request = fake.last_request();
+
+    assert_eq!(request.headers[USER_AGENT], DEFAULT_USER_AGENT);
+    assert!(request.headers.get(AUTHORIZATION).is_none());
+}
+
+#[tokio::test]
+async fn it_sends_the_default_headers_with_a_custom_client() {
+    let fake = FakeTransport::default();
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .client(reqwest::Client::new())
+        .user_agent("my-service/1.0")
+        .default_header(
+            HeaderName::from_static("x-client"),
+            HeaderValue::from_static("tests"),
+        )
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+    let _ = client
+        .anime("missing", AnimeInclude::default(), Fieldsets::default())
+        .await;
+    let request = fake.last_request();
+
+    assert_eq!(request.headers[USER_AGENT], "my-service/1.0");
+    assert_eq!(request.headers["x-client"], "tests");
+}
+
+#[test]
+fn it_hides_the_token_in_debug_output() {
+    let client = AnimeThemesClient::builder()
+        .token("secret-token")
+        .build()
+        .unwrap();
+
+    assert!(!format!("{client:?}").contains("secret-token"))
+}
+
+#[tokio::test]
+async fn it_requires_a_token_for_the_current_user() {
+    let fake = FakeTransport::default().route(
+        "/me",
+        &[],
+        requires_token(json!({"user": {"id": 1, "name": "user"}})),
+    );
+    let result = fake
+        .client()
+        .me(UserInclude::default().playlists(), Fieldsets::default())
+        .await;
+
+    assert!(matches!(result, Err(ApiError::Unauthorized { .. })))
+}
+
+#[tokio::test]
+async fn it_returns_anime_by_year() {
+    let fake = FakeTransport::default()
+        .json("/animeyear", json!([2019, 2020, 2021]))
+        .json(
+            "/animeyear/2021",
+            json!({"winter": [], "spring": [vivy()], "summer": [], "fall": []}),
+        );
+    let client = fake.client();
+    let years = client.anime_years().await.unwrap();
+    assert!(years.contains(&2021));
+
+    let result = client
+        .anime_year(2021, AnimeInclude::default(), Fieldsets::default())
+        .await
+        .unwrap();
+
+    assert!(result
+        .season(AnimeSeason::Spring)
+        .iter()
+        .any(|anime| anime.slug.as_deref() == Some("vivy_fluorite_eyes_song")))
+}
+
+#[tokio::test]
+async fn it_returns_the_current_featured_theme() {
+    let fake = FakeTransport::default().json(
+        "/current/featuredtheme",
+        json!({"featuredtheme": {"id": 1, "start_at": "2023-11-01T00:00:00.000000Z", "end_at": null, "video": kimiuso_op2()}}),
+    );
+    let result = fake
+        .client()
+        .current_featured_theme(
+            FeaturedThemeInclude::default().video(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert!(result.video.is_some())
+}
+
+fn dump() -> Value {
+    json!({
+        "id": 42,
+        "path": "animethemes-db-dump-wiki-1700000000.sql",
+        "link": "https://dump.animethemes.test/animethemes-db-dump-wiki-1700000000.sql",
+        "created_at": "2023-11-14T22:13:20.000000Z",
+    })
+}
+
+#[tokio::test]
+async fn it_lists_announcements_and_dumps() {
+    let fake = FakeTransport::default()
+        .json(
+            "/announcement",
+            page(
+                "/announcement",
+                "announcements",
+                vec![json!({"id": 1, "content": "Maintenance"})],
+                1,
+                15,
+                false,
+            ),
+        )
+        .json("/dump", page("/dump", "dumps", vec![dump()], 1, 15, false));
+    let client = fake.client();
+    client
+        .list_announcements(
+            Pagination::default(),
+            AnnouncementFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    let dumps = client
+        .list_dumps(
+            Pagination::default(),
+            DumpFilter::default(),
+            Sort::desc(DumpSort::CreatedAt),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fake.last_request().query("sort").as_deref(),
+        Some("-created_at")
+    );
+    assert!(!dumps.data.is_empty())
+}
+
+#[tokio::test]
+async fn it_downloads_the_latest_dump() {
+    let content = "INSERT INTO `songs` (`song_id`, `title`) VALUES (1,'Redo');";
+    let fake = FakeTransport::default()
+        .json("/dump", page("/dump", "dumps", vec![dump()], 1, 1, false))
+        .route("/animethemes-db-dump-wiki-1700000000.sql", &[], move |_| {
+            FakeResponse {
+                status: StatusCode::OK,
+                headers: Default::default(),
+                body: content.to_string(),
+            }
+        });
+    let client = fake.client();
+    let dump = client
+        .latest_dump(DumpFilter::default())
+        .await
+        .unwrap()
+        .unwrap();
+    let mut downloaded = Vec::new();
+    let mut reported = 0;
+    let size = client
+        .download_dump(&dump, &mut downloaded, |progress| {
+            reported = progress.downloaded
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(size, content.len() as u64);
+    assert_eq!(downloaded, content.as_bytes());
+    assert_eq!(reported, size);
+}
+
+#[tokio::test]
+async fn it_requires_the_link_to_download_dumps() {
+    let dump: Dump = serde_json::from_value(json!({"id": 1, "path": "dump.sql"})).unwrap();
+    let mut downloaded = Vec::new();
+    let result = FakeTransport::default()
+        .client()
+        .download_dump(&dump, &mut downloaded, |_| {})
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ApiError::MissingAttribute { name }) if name == "link"
+    ));
+}
+
+#[tokio::test]
+async fn it_caches_responses() {
+    let fake = FakeTransport::default().json(
+        "/anime/bakemonogatari",
+        json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+    );
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .cache(ResponseCache::memory(10))
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        client
+            .anime(
+                "bakemonogatari",
+                AnimeInclude::default(),
+                Fieldsets::default(),
+            )
+            .await
+            .unwrap();
+    }
+    let stats = client.cache_stats().unwrap();
+
+    assert_eq!(fake.requests().len(), 1);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 1);
+}
+
+#[tokio::test]
+async fn it_revalidates_expired_responses() {
+    let fake = FakeTransport::default().route("/anime/bakemonogatari", &[], |request| {
+        if request.headers.get(IF_NONE_MATCH).is_some() {
+            FakeResponse::json(StatusCode::NOT_MODIFIED, Value::Null)
+        } else {
+            FakeResponse::json(
+                StatusCode::OK,
+                json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+            )
+            .header("etag", "\"v1\"")
+        }
+    });
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .cache(ResponseCache::memory(10).ttl(std::time::Duration::ZERO))
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        let anime = client
+            .anime(
+                "bakemonogatari",
+                AnimeInclude::default(),
+                Fieldsets::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+    }
+    let stats = client.cache_stats().unwrap();
+
+    assert_eq!(fake.requests().len(), 2);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.revalidations, 1);
+}
+
+#[tokio::test]
+async fn it_caches_responses_per_token() {
+    let fake = FakeTransport::default().route("/playlist", &[], |request| {
+        let name = match request.headers.get(AUTHORIZATION) {
+            Some(_) => "Private",
+            None => "Public",
+        };
+
+        FakeResponse::json(
+            StatusCode::OK,
+            page(
+                "/playlist",
+                "playlists",
+                vec![json!({"id": "aBc1", "name": name})],
+                1,
+                15,
+                false,
+            ),
+        )
+    });
+    let cache = ResponseCache::memory(10);
+    let builder = || {
+        AnimeThemesClient::builder()
+            .api_endpoint(FAKE_ENDPOINT)
+            .transport(fake.clone())
+            .cache(cache.clone())
+    };
+    let names = |client: AnimeThemesClient| async move {
+        client
+            .list_playlists(
+                Pagination::default(),
+                PlaylistInclude::default(),
+                PlaylistFilter::default(),
+                Sort::default(),
+                Fieldsets::default(),
+            )
+            .await
+            .unwrap()
+            .data[0]
+            .name
+            .clone()
+    };
+
+    assert_eq!(
+        names(builder().build().unwrap()).await.as_deref(),
+        Some("Public")
+    );
+    assert_eq!(
+        names(builder().token("secret-token").build().unwrap())
+            .await
+            .as_deref(),
+        Some("Private")
+    );
+    assert_eq!(fake.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn it_evicts_cached_playlists_after_writes() {
+    let fake = FakeTransport::default()
+        .json(
+            "/playlist",
+            page("/playlist", "playlists", Vec::new(), 1, 15, false),
+        )
+        .json(
+            "/me/playlist",
+            page("/me/playlist", "playlists", Vec::new(), 1, 15, false),
+        )
+        .route(
+            "/playlist/aBc1",
+            &[],
+            requires_token(json!({"playlist": {"id": "aBc1", "name": "renamed"}})),
+        );
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .token("secret-token")
+        .cache(ResponseCache::memory(10))
+        .build()
+        .unwrap();
+    let list = || {
+        client.list_playlists(
+            Pagination::default(),
+            PlaylistInclude::default(),
+            PlaylistFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+    };
+    let mine = || {
+        client.my_playlists(
+            Pagination::default(),
+            PlaylistInclude::default(),
+            PlaylistFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+    };
+    let gets = |path: &str| {
+        fake.requests()
+            .iter()
+            .filter(|request| request.method == Method::GET && request.url.path() == path)
+            .count()
+    };
+
+    for _ in 0..2 {
+        list().await.unwrap();
+        mine().await.unwrap();
+    }
+    assert_eq!(gets("/playlist"), 1);
+    assert_eq!(gets("/me/playlist"), 1);
+
+    client
+        .update_playlist(
+            "aBc1",
+            &PlaylistUpdate {
+                name: Some("renamed".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    list().await.unwrap();
+    mine().await.unwrap();
+
+    assert_eq!(gets("/playlist"), 2);
+    assert_eq!(gets("/me/playlist"), 2);
+}
+
+#[tokio::test]
+async fn it_caches_responses_per_endpoint() {
+    let fake = FakeTransport::default()
+        .json(
+            "/anime/bakemonogatari",
+            json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+        )
+        .json(
+            "/staging/anime/bakemonogatari",
+            json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari (staging)", 2009, "Summer")}),
+        );
+    let cache = ResponseCache::memory(10);
+    let name = |endpoint: String| {
+        let client = AnimeThemesClient::builder()
+            .api_endpoint(endpoint)
+            .transport(fake.clone())
+            .cache(cache.clone())
+            .build()
+            .unwrap();
+
+        async move {
+            client
+                .anime(
+                    "bakemonogatari",
+                    AnimeInclude::default(),
+                    Fieldsets::default(),
+                )
+                .await
+                .unwrap()
+                .name
+        }
+    };
+
+    assert_eq!(
+        name(FAKE_ENDPOINT.to_string()).await.as_deref(),
+        Some("Bakemonogatari")
+    );
+    assert_eq!(
+        name(format!("{FAKE_ENDPOINT}/staging")).await.as_deref(),
+        Some("Bakemonogatari (staging)")
+    );
+}
+
+#[tokio::test]
+async fn it_returns_an_error_for_unexpected_not_modified_responses() {
+    let fake = FakeTransport::default().status(
+        "/anime/bakemonogatari",
+        StatusCode::NOT_MODIFIED,
+        Value::Null,
+    );
+    let result = fake
+        .client()
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ApiError::Server {
+            status: StatusCode::NOT_MODIFIED,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn it_retries_transient_errors() {
+    let attempts = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = attempts.clone();
+    let fake = FakeTransport::default().route("/anime/bakemonogatari", &[], move |_| {
+        if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+            FakeResponse::json(StatusCode::BAD_GATEWAY, Value::Null)
+        } else {
+            FakeResponse::json(
+                StatusCode::OK,
+                json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+            )
+        }
+    });
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .retry(
+            crate::retry::RetryPolicy::default()
+                .backoff(std::time::Duration::ZERO, std::time::Duration::ZERO),
+        )
+        .build()
+        .unwrap();
+    client
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(fake.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn it_doesnt_retry_before_retry_after() {
+    let fake = FakeTransport::default().route("/anime/bakemonogatari", &[], |_| {
+        FakeResponse::json(StatusCode::TOO_MANY_REQUESTS, Value::Null).header("retry-after", "3600")
+    });
+    let client = AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(fake.clone())
+        .build()
+        .unwrap();
+    let result = client
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ApiError::RateLimited { retry_after: Some(delay) }) if delay.as_secs() == 3600
+    ));
+    assert_eq!(fake.requests().len(), 1);
+}
@@ -0,0 +1,93 @@
+use crate::client::DEFAULT_VIDEO_ENDPOINT;
+use crate::dump::DumpData;
+use crate::models::{AnimeSeason, ThemeType, VideoOverlap};
+use std::io::BufReader;
+
+pub(super) static DUMP: &str = r#"
+-- MySQL dump 10.13
+/*!40101 SET NAMES utf8mb4 */;
+
+DROP TABLE IF EXISTS `anime`;
+CREATE TABLE `anime` (
+  `anime_id` bigint unsigned NOT NULL AUTO_INCREMENT,
+  `created_at` timestamp NULL DEFAULT NULL,
+  `updated_at` timestamp NULL DEFAULT NULL,
+  `deleted_at` timestamp NULL DEFAULT NULL,
+  `slug` varchar(192) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `name` varchar(192) COLLATE utf8mb4_unicode_ci NOT NULL,
+  `year` int DEFAULT NULL,
+  `season` int DEFAULT NULL,
+  `synopsis` text COLLATE utf8mb4_unicode_ci,
+  PRIMARY KEY (`anime_id`),
+  KEY `anime_slug_index` (`slug`)
+) ENGINE=InnoDB;
+
+LOCK TABLES `anime` WRITE;
+INSERT INTO `anime` VALUES (1,'2020-01-01 00:00:00','2020-01-01 00:00:00',NULL,'bakemonogatari','Bakemonogatari',2009,2,'It\'s about \"oddities\";\nand more'),(2,'2020-01-01 00:00:00','2020-01-01 00:00:00',NULL,'kimi_no_na_wa','Kimi no Na wa.',2016,3,NULL);
+UNLOCK TABLES;
+
+INSERT INTO `anime_themes` (`theme_id`, `type`, `sequence`, `anime_id`, `song_id`) VALUES (10,0,1,1,20),(11,1,NULL,2,NULL);
+INSERT INTO `anime_theme_entries` (`entry_id`, `version`, `episodes`, `nsfw`, `spoiler`, `theme_id`) VALUES (30,1,'1-12',0,1,10);
+INSERT INTO `videos` (`video_id`, `basename`, `resolution`, `nc`, `overlap`) VALUES (40,'Bakemonogatari-OP1.webm',720,1,2);
+INSERT INTO `anime_theme_entry_video` (`entry_id`, `video_id`) VALUES (30,40);
+INSERT INTO `songs` (`song_id`, `title`) VALUES (20,'Staple Stable');
+INSERT INTO `artists` (`artist_id`, `slug`, `name`) VALUES (50,'chiwa_saito','Chiwa Saito');
+INSERT INTO `artist_song` (`artist_id`, `song_id`, `as`) VALUES (50,20,'Hitagi Senjougahara');
+"#;
+
+#[test]
+fn it_imports_a_dump() {
+    let data = DumpData::from_sql(DUMP.as_bytes(), DEFAULT_VIDEO_ENDPOINT).unwrap();
+
+    assert_eq!(data.anime.len(), 2);
+    let anime = &data.anime[0];
+    assert_eq!(anime.meta.id, Some(1));
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+    assert_eq!(anime.season, Some(AnimeSeason::Summer));
+    assert_eq!(
+        anime.synopsis.as_deref(),
+        Some("It's about \"oddities\";\nand more")
+    );
+    assert_eq!(data.anime[1].season, Some(AnimeSeason::Fall));
+
+    assert!(matches!(data.themes[0].theme_type, Some(ThemeType::OP)));
+    assert_eq!(data.themes[0].slug.as_deref(), Some("OP1"));
+    assert_eq!(data.themes[1].slug.as_deref(), Some("ED"));
+    assert_eq!(data.entries[0].spoiler, Some(true));
+    assert_eq!(data.entries[0].nsfw, Some(false));
+    assert_eq!(data.videos[0].no_credits, Some(true));
+    assert!(matches!(data.videos[0].overlap, Some(VideoOverlap::Over)));
+    assert!(data.videos[0]
+        .link
+        .as_ref()
+        .is_some_and(|link| link.ends_with("Bakemonogatari-OP1.webm")));
+    assert_eq!(data.songs[0].title.as_deref(), Some("Staple Stable"));
+    assert_eq!(data.artists[0].slug.as_deref(), Some("chiwa_saito"));
+
+    assert_eq!(data.relations.theme_anime, vec![(10, 1), (11, 2)]);
+    assert_eq!(data.relations.theme_song, vec![(10, 20)]);
+    assert_eq!(data.relations.entry_theme, vec![(30, 10)]);
+    assert_eq!(data.relations.entry_video, vec![(30, 40)]);
+    assert_eq!(data.relations.artist_song, vec![(50, 20)]);
+    assert_eq!(
+        data.relations.song_credits,
+        vec![(50, 20, "Hitagi Senjougahara".to_string())]
+    );
+}
+
+#[test]
+fn it_reads_a_dump_in_small_chunks() {
+    let reader = BufReader::with_capacity(16, DUMP.as_bytes());
+    let data = DumpData::from_sql(reader, "https://v.animethemes.test/").unwrap();
+
+    assert_eq!(data.anime.len(), 2);
+    assert_eq!(
+        data.anime[0].synopsis.as_deref(),
+        Some("It's about \"oddities\";\nand more")
+    );
+    assert_eq!(
+        data.videos[0].link.as_deref(),
+        Some("https://v.animethemes.test/Bakemonogatari-OP1.webm")
+    );
+    assert_eq!(data.relations.artist_song, vec![(50, 20)]);
+}
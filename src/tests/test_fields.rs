@@ -0,0 +1,45 @@
+use crate::fields::*;
+use crate::models::{Anime, Video};
+
+#[test]
+fn it_serializes_fieldsets() {
+    let fields = Fieldsets {
+        search: SearchResultFields::default().anime().videos(),
+        videos: VideoFields::default().basename().link().resolution(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        fields.query(),
+        vec![
+            ("fields[search]".to_string(), "anime,videos".to_string()),
+            (
+                "fields[video]".to_string(),
+                "basename,resolution,link".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn it_deserializes_sparse_models() {
+    let video: Video = serde_json::from_str(
+        r#"{"basename": "KimiUso-OP2.webm", "resolution": 720, "link": "https://v.animethemes.moe/KimiUso-OP2.webm"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(video.basename.as_deref(), Some("KimiUso-OP2.webm"));
+    assert_eq!(video.resolution, Some(720));
+    assert!(video.path.is_none());
+    assert!(video.overlap.is_none());
+}
+
+#[test]
+fn it_leaves_omitted_attributes_empty() {
+    let anime: Anime = serde_json::from_str(r#"{"name": "Bakemonogatari"}"#).unwrap();
+
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+    assert!(anime.meta.id.is_none());
+    assert!(anime.slug.is_none());
+    assert!(anime.year.is_none());
+}
@@ -0,0 +1,81 @@
+use crate::filters::*;
+use crate::models::{AnimeSeason, PlaylistVisibility, ThemeType, VideoOverlap, VideoSource};
+
+#[test]
+fn it_serializes_filters() {
+    let filter = AnimeFilter::default()
+        .year(2021)
+        .season(AnimeSeason::Spring)
+        .has("animethemes")
+        .trashed(Trashed::Without);
+
+    assert_eq!(
+        filter.query(false),
+        vec![
+            ("filter[year]".to_string(), "2021".to_string()),
+            ("filter[season]".to_string(), "Spring".to_string()),
+            ("filter[has]".to_string(), "animethemes".to_string()),
+            ("filter[trashed]".to_string(), "without".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn it_serializes_scoped_filters_with_comparisons() {
+    let filter = VideoFilter::default()
+        .resolution_cmp(Comparison::Gte, 1080)
+        .source(VideoSource::BD);
+
+    assert_eq!(
+        filter.query(true),
+        vec![
+            (
+                "filter[video][resolution][gte]".to_string(),
+                "1080".to_string()
+            ),
+            ("filter[video][source]".to_string(), "BD".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn it_combines_search_filters() {
+    let filters = SearchFilters {
+        anime: AnimeFilter::default().year(2021),
+        videos: VideoFilter::default().no_credits(true),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        filters.query(),
+        vec![
+            ("filter[anime][year]".to_string(), "2021".to_string()),
+            ("filter[video][nc]".to_string(), "true".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn it_serializes_enum_filter_values() {
+    let filter = ThemeFilter::default().theme_type(ThemeType::ED);
+    let videos = VideoFilter::default()
+        .overlap(VideoOverlap::Transition)
+        .source(VideoSource::WEB);
+    let playlists = PlaylistFilter::default().visibility(PlaylistVisibility::Unlisted);
+
+    assert_eq!(
+        filter.query(false),
+        vec![("filter[type]".to_string(), "ED".to_string())]
+    );
+    assert_eq!(
+        videos.query(false),
+        vec![
+            ("filter[overlap]".to_string(), "Transition".to_string()),
+            ("filter[source]".to_string(), "WEB".to_string()),
+        ]
+    );
+    assert_eq!(
+        playlists.query(false),
+        vec![("filter[visibility]".to_string(), "Unlisted".to_string())]
+    );
+}
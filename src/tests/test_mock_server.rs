@@ -0,0 +1,213 @@
+use crate::catalogue::Catalogue;
+use crate::client::{AnimeThemesClient, DEFAULT_VIDEO_ENDPOINT};
+use crate::dump::DumpData;
+use crate::error::ApiError;
+use crate::fields::Fieldsets;
+use crate::filters::{AnimeFilter, SearchFilters};
+use crate::includes::*;
+use crate::mock_server::{handle, MockServer};
+use crate::pagination::{PaginateOptions, Pagination};
+use crate::sort::{SearchSorts, Sort};
+use crate::tests::test_dump::DUMP;
+use futures::StreamExt;
+use hyper::{Method, StatusCode};
+
+fn catalogue() -> Catalogue {
+    Catalogue::from(DumpData::from_sql(DUMP.as_bytes(), DEFAULT_VIDEO_ENDPOINT).unwrap())
+}
+
+fn start() -> (MockServer, AnimeThemesClient) {
+    let server = MockServer::start(catalogue()).unwrap();
+    let client = AnimeThemesClient::new(server.url(), "");
+
+    (server, client)
+}
+
+#[tokio::test]
+async fn it_serves_anime_with_includes() {
+    let (_server, client) = start();
+    let anime = client
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default()
+                .themes_entries_videos()
+                .themes_song_artists(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(anime.name.as_deref(), Some("Bakemonogatari"));
+    let theme = &anime.themes.unwrap()[0];
+    assert_eq!(theme.slug.as_deref(), Some("OP1"));
+    let entries = theme.entries.as_ref().unwrap();
+    assert_eq!(
+        entries[0].videos.as_ref().unwrap()[0].basename.as_deref(),
+        Some("Bakemonogatari-OP1.webm")
+    );
+    let artist = &theme.song.as_ref().unwrap().artists.as_ref().unwrap()[0];
+    assert_eq!(artist.slug.as_deref(), Some("chiwa_saito"));
+    assert_eq!(artist.as_character.as_deref(), Some("Hitagi Senjougahara"));
+}
+
+#[tokio::test]
+async fn it_serves_videos_by_basename() {
+    let (_server, client) = start();
+    let video = client
+        .video(
+            "Bakemonogatari-OP1.webm",
+            VideoInclude::default().entries_theme_anime().audio(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(video.resolution, Some(720));
+    assert!(video.audio.is_none());
+    let entry = &video.entries.unwrap()[0];
+    let theme = entry.theme.as_ref().unwrap();
+    assert_eq!(
+        theme.anime.as_ref().unwrap().slug.as_deref(),
+        Some("bakemonogatari")
+    );
+}
+
+#[tokio::test]
+async fn it_searches_the_catalogue() {
+    let (_server, client) = start();
+    let result = client
+        .search(
+            "bakemono",
+            Fieldsets::default(),
+            SearchIncludes::all(),
+            SearchFilters::default(),
+            SearchSorts::default(),
+        )
+        .await
+        .unwrap();
+
+    let anime = result.anime.unwrap();
+    assert_eq!(anime[0].slug.as_deref(), Some("bakemonogatari"));
+    assert!(anime[0].images.as_ref().unwrap().is_empty());
+    let themes = result.themes.unwrap();
+    assert_eq!(
+        themes[0].anime.as_ref().unwrap().slug.as_deref(),
+        Some("bakemonogatari")
+    );
+    assert!(result.artists.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn it_paginates_index_endpoints() {
+    let (_server, client) = start();
+    let first = client
+        .list_anime(
+            Pagination::new(1, 1),
+            AnimeInclude::default(),
+            AnimeFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(first.data[0].slug.as_deref(), Some("bakemonogatari"));
+    assert!(first.links.prev.is_none());
+    assert!(first
+        .links
+        .next
+        .as_deref()
+        .unwrap()
+        .contains("page%5Bnumber%5D=2"));
+
+    let anime: Vec<_> = client
+        .paginate(PaginateOptions::default().page_size(1), |client, page| {
+            client.list_anime(
+                page,
+                AnimeInclude::default(),
+                AnimeFilter::default(),
+                Sort::default(),
+                Fieldsets::default(),
+            )
+        })
+        .collect()
+        .await;
+
+    assert_eq!(anime.len(), 2);
+    assert_eq!(
+        anime[1].as_ref().unwrap().slug.as_deref(),
+        Some("kimi_no_na_wa")
+    );
+}
+
+#[tokio::test]
+async fn it_returns_error_responses() {
+    let (_server, client) = start();
+    let missing = client
+        .anime("missing", AnimeInclude::default(), Fieldsets::default())
+        .await;
+    assert!(matches!(missing, Err(ApiError::NotFound)));
+
+    let too_large = client
+        .list_anime(
+            Pagination::default().size(500),
+            AnimeInclude::default(),
+            AnimeFilter::default(),
+            Sort::default(),
+            Fieldsets::default(),
+        )
+        .await;
+    assert!(matches!(
+        too_large,
+        Err(ApiError::Server { status: StatusCode::UNPROCESSABLE_ENTITY, ref errors }) if !errors.is_empty()
+    ));
+}
+
+#[test]
+fn it_rejects_unknown_includes() {
+    let query = [("include".to_string(), "animethemes.nope".to_string())];
+    let (status, body) = handle(
+        &catalogue(),
+        "http://localhost",
+        &Method::GET,
+        "/anime/bakemonogatari",
+        &query,
+    );
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errors"][0]["title"], "Invalid Include");
+}
+
+#[test]
+fn it_rejects_unsupported_methods_and_routes() {
+    let catalogue = catalogue();
+    let (status, _) = handle(&catalogue, "", &Method::POST, "/anime", &[]);
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+
+    let (status, _) = handle(&catalogue, "", &Method::GET, "/playlist/abc/track", &[]);
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn it_rejects_page_numbers_out_of_range() {
+    let catalogue = catalogue();
+    let page = |number: u32| {
+        let query = [
+            ("page[size]".to_string(), "100".to_string()),
+            ("page[number]".to_string(), number.to_string()),
+        ];
+
+        handle(&catalogue, "", &Method::GET, "/anime", &query)
+    };
+
+    let (status, body) = page(u32::MAX / 10);
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["errors"]["page.number"].is_array());
+
+    let (status, _) = page(u32::MAX);
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, body) = page(2);
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["anime"].as_array().unwrap().is_empty());
+}
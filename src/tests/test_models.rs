@@ -0,0 +1,87 @@
+use crate::models::{Artist, PlaylistTrack};
+
+#[test]
+fn it_walks_artist_memberships() {
+    let artist: Artist = serde_json::from_str(
+        r#"{
+            "id": 1,
+            "name": "Clara",
+            "slug": "clara",
+            "groups": [
+                {
+                    "id": 2,
+                    "name": "ClariS",
+                    "slug": "claris",
+                    "artistmember": {"alias": "Clara", "as": null}
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(artist.member_of().count(), 1);
+    assert_eq!(artist.group_members().count(), 0);
+    assert_eq!(
+        artist.membership_credits(),
+        vec!["member of ClariS as Clara".to_string()]
+    );
+}
+
+#[test]
+fn it_uses_member_aliases_as_credited_name() {
+    let group: Artist = serde_json::from_str(
+        r#"{
+            "name": "ClariS",
+            "slug": "claris",
+            "members": [
+                {"name": "Karen", "slug": "karen", "artistmember": {"alias": "Karen-chan"}},
+                {"name": "Clara", "slug": "clara"}
+            ]
+        }"#,
+    )
+    .unwrap();
+    let names: Vec<&str> = group
+        .group_members()
+        .filter_map(Artist::credited_name)
+        .collect();
+
+    assert_eq!(names, vec!["Karen-chan", "Clara"]);
+}
+
+#[test]
+fn it_orders_playlist_tracks_by_links() {
+    let tracks: Vec<PlaylistTrack> = serde_json::from_str(
+        r#"[
+            {"id": "b", "previous": {"id": "a"}, "next": {"id": "c"}},
+            {"id": "c", "previous": {"id": "b"}, "next": null},
+            {"id": "x", "previous": null, "next": null},
+            {"id": "a", "previous": null, "next": {"id": "b"}}
+        ]"#,
+    )
+    .unwrap();
+    let ids: Vec<String> = PlaylistTrack::playback_order(tracks)
+        .into_iter()
+        .filter_map(|track| track.meta.id)
+        .collect();
+
+    assert_eq!(ids, vec!["a", "b", "c", "x"]);
+}
+
+#[test]
+fn it_orders_playlist_tracks_by_previous_links() {
+    let tracks: Vec<PlaylistTrack> = serde_json::from_str(
+        r#"[
+            {"id": "c", "previous": {"id": "b"}},
+            {"id": "a", "previous": null},
+            {"id": "d", "previous": {"id": "c"}},
+            {"id": "b", "previous": {"id": "a"}}
+        ]"#,
+    )
+    .unwrap();
+    let ids: Vec<String> = PlaylistTrack::playback_order(tracks)
+        .into_iter()
+        .filter_map(|track| track.meta.id)
+        .collect();
+
+    assert_eq!(ids, vec!["a", "b", "c", "d"]);
+}
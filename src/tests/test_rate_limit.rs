@@ -0,0 +1,54 @@
+use crate::client::AnimeThemesClient;
+use crate::rate_limit::RateLimiter;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[tokio::test(start_paused = true)]
+async fn it_waits_for_the_bucket_to_refill() {
+    let limiter = RateLimiter::new(2, Duration::from_secs(1));
+    let start = Instant::now();
+    limiter.acquire().await;
+    limiter.acquire().await;
+    assert_eq!(start.elapsed(), Duration::ZERO);
+
+    limiter.acquire().await;
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_adapts_to_response_headers() {
+    let limiter = RateLimiter::per_minute(90);
+    let mut headers = HeaderMap::new();
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from_static("0"));
+    limiter.update(&headers);
+    assert_eq!(limiter.available(), 0);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+    limiter.update(&headers);
+    let start = Instant::now();
+    limiter.acquire().await;
+
+    assert!(start.elapsed() >= Duration::from_secs(5));
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_is_shared_between_clones() {
+    let limiter = RateLimiter::new(1, Duration::from_secs(10));
+    let clone = limiter.clone();
+    limiter.acquire().await;
+
+    assert_eq!(clone.available(), 0);
+}
+
+#[test]
+fn it_only_limits_clients_configured_with_a_limiter() {
+    let unlimited = AnimeThemesClient::builder().build().unwrap();
+    let limited = AnimeThemesClient::builder()
+        .rate_limit(RateLimiter::default())
+        .build()
+        .unwrap();
+
+    assert!(unlimited.rate_limiter().is_none());
+    assert!(limited.rate_limiter().is_some());
+}
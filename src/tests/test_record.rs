@@ -0,0 +1,158 @@
+use crate::client::AnimeThemesClient;
+use crate::error::ApiError;
+use crate::fields::Fieldsets;
+use crate::includes::AnimeInclude;
+use crate::retry::RetryPolicy;
+use crate::tests::fake::*;
+use crate::transport::{Recorder, Replayer, Transport};
+use reqwest::{Method, Request, StatusCode};
+use serde_json::json;
+use std::path::PathBuf;
+
+fn fixture_directory(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "animethemes-fixtures-{name}-{}",
+        std::process::id()
+    ))
+}
+
+fn client<T: Transport + 'static>(transport: T) -> AnimeThemesClient {
+    AnimeThemesClient::builder()
+        .api_endpoint(FAKE_ENDPOINT)
+        .transport(transport)
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+fn get(url: &str) -> Request {
+    Request::new(Method::GET, url.parse().unwrap())
+}
+
+#[tokio::test]
+async fn it_replays_recorded_responses() {
+    let directory = fixture_directory("replay");
+    let fake = FakeTransport::default().route("/anime/bakemonogatari", &[], |_| {
+        FakeResponse::json(
+            StatusCode::OK,
+            json!({"anime": anime(1, "bakemonogatari", "Bakemonogatari", 2009, "Summer")}),
+        )
+        .header("etag", "\"v1\"")
+    });
+    let recorded = client(Recorder::new(fake.clone(), &directory))
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default().themes(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+
+    let replayer = Replayer::load(&directory).unwrap();
+    assert_eq!(replayer.len(), 1);
+
+    let replayed = client(replayer)
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default().themes(),
+            Fieldsets::default(),
+        )
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(fake.requests().len(), 1);
+    assert_eq!(recorded.meta.id, replayed.meta.id);
+    assert_eq!(replayed.name.as_deref(), Some("Bakemonogatari"));
+}
+
+#[tokio::test]
+async fn it_matches_fixtures_regardless_of_query_order() {
+    let directory = fixture_directory("query");
+    let fake = FakeTransport::default().json("/search", json!({"search": {}}));
+    let recorder = Recorder::new(fake, &directory);
+    recorder
+        .execute(get(
+            "https://api.animethemes.test/search?q=vivy&include[anime]=animethemes,images",
+        ))
+        .await
+        .unwrap();
+
+    let replayer = Replayer::load(&directory).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+    let response = replayer
+        .execute(get(
+            "https://api.animethemes.moe/search?include[anime]=images,animethemes&q=vivy",
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), r#"{"search":{}}"#);
+}
+
+#[tokio::test]
+async fn it_replays_error_responses() {
+    let directory = fixture_directory("errors");
+    let recorder = Recorder::new(FakeTransport::default(), &directory);
+    let recorded = client(recorder)
+        .anime("missing", AnimeInclude::default(), Fieldsets::default())
+        .await;
+    assert!(matches!(recorded, Err(ApiError::NotFound)));
+
+    let replayer = Replayer::load(&directory).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+    let replayed = client(replayer)
+        .anime("missing", AnimeInclude::default(), Fieldsets::default())
+        .await;
+
+    assert!(matches!(replayed, Err(ApiError::NotFound)));
+}
+
+#[tokio::test]
+async fn it_fails_without_a_fixture() {
+    let result = client(Replayer::default())
+        .anime(
+            "bakemonogatari",
+            AnimeInclude::default(),
+            Fieldsets::default(),
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ApiError::MissingFixture(request)) if request == "GET /anime/bakemonogatari?"
+    ));
+}
+
+#[tokio::test]
+async fn it_records_requests_with_different_bodies_separately() {
+    let directory = fixture_directory("bodies");
+    let fake = FakeTransport::default().route("/playlist", &[], |request| {
+        let name = request.body.as_ref().unwrap()["name"].clone();
+
+        FakeResponse::json(StatusCode::CREATED, json!({"playlist": {"name": name}}))
+    });
+    let post = |name: &str| {
+        let mut request = Request::new(
+            Method::POST,
+            "https://api.animethemes.test/playlist".parse().unwrap(),
+        );
+        *request.body_mut() = Some(json!({"name": name}).to_string().into());
+
+        request
+    };
+    let recorder = Recorder::new(fake, &directory);
+    recorder.execute(post("first")).await.unwrap();
+    recorder.execute(post("second")).await.unwrap();
+
+    let replayer = Replayer::load(&directory).unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+    let response = replayer.execute(post("second")).await.unwrap();
+
+    assert_eq!(replayer.len(), 2);
+    assert_eq!(
+        response.text().await.unwrap(),
+        r#"{"playlist":{"name":"second"}}"#
+    );
+}
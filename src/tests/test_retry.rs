@@ -0,0 +1,134 @@
+use crate::error::{parse_retry_after, ApiError};
+use crate::retry::RetryPolicy;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use std::time::{Duration, SystemTime};
+
+fn server_error(status: StatusCode) -> ApiError {
+    ApiError::Server {
+        status,
+        errors: Vec::new(),
+    }
+}
+
+#[test]
+fn it_backs_off_exponentially() {
+    let policy = RetryPolicy::default()
+        .jitter(0.0)
+        .backoff(Duration::from_millis(500), Duration::from_secs(3));
+    let error = server_error(StatusCode::BAD_GATEWAY);
+
+    assert_eq!(policy.delay(1, &error), Some(Duration::from_millis(500)));
+    assert_eq!(policy.delay(2, &error), Some(Duration::from_secs(1)));
+    assert_eq!(policy.delay(3, &error), Some(Duration::from_secs(2)));
+    assert_eq!(policy.delay(4, &error), Some(Duration::from_secs(3)));
+}
+
+#[test]
+fn it_applies_jitter_within_bounds() {
+    let policy = RetryPolicy::default()
+        .jitter(0.5)
+        .backoff(Duration::from_secs(1), Duration::from_secs(10));
+    let error = server_error(StatusCode::BAD_GATEWAY);
+
+    for _ in 0..100 {
+        let delay = policy.delay(1, &error).unwrap();
+        assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn it_honors_retry_after() {
+    let error = ApiError::RateLimited {
+        retry_after: Some(Duration::from_secs(7)),
+    };
+
+    assert_eq!(
+        RetryPolicy::default().delay(1, &error),
+        Some(Duration::from_secs(7))
+    );
+    assert!(
+        RetryPolicy::default()
+            .respect_retry_after(false)
+            .delay(1, &error)
+            .unwrap()
+            < Duration::from_secs(7)
+    );
+}
+
+#[test]
+fn it_gives_up_if_retry_after_exceeds_the_max_backoff() {
+    let policy =
+        RetryPolicy::default().backoff(Duration::from_millis(500), Duration::from_secs(30));
+    let rate_limited = |seconds: u64| ApiError::RateLimited {
+        retry_after: Some(Duration::from_secs(seconds)),
+    };
+
+    assert_eq!(
+        policy.delay(1, &rate_limited(30)),
+        Some(Duration::from_secs(30))
+    );
+    assert_eq!(policy.delay(1, &rate_limited(60 * 60)), None);
+}
+
+#[test]
+fn it_parses_retry_after_dates() {
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+    assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(120)));
+
+    let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+    headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+    let retry_after = parse_retry_after(&headers).unwrap();
+    assert!(retry_after > Duration::from_secs(100) && retry_after <= Duration::from_secs(120));
+
+    headers.insert(
+        RETRY_AFTER,
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+    );
+    assert_eq!(parse_retry_after(&headers), Some(Duration::ZERO));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+    assert_eq!(parse_retry_after(&headers), None);
+}
+
+#[test]
+fn it_doesnt_overflow_with_large_backoffs() {
+    let error = server_error(StatusCode::BAD_GATEWAY);
+    let policy = RetryPolicy::default()
+        .jitter(0.0)
+        .multiplier(1e10)
+        .backoff(Duration::from_secs(u64::MAX / 2), Duration::MAX);
+
+    assert_eq!(policy.delay(33, &error), Some(Duration::MAX));
+
+    let policy = RetryPolicy::default()
+        .jitter(0.0)
+        .multiplier(1e10)
+        .backoff(Duration::from_secs(1), Duration::from_secs(30));
+
+    assert_eq!(policy.delay(10, &error), Some(Duration::from_secs(30)));
+}
+
+#[test]
+fn it_only_retries_transient_errors() {
+    let policy = RetryPolicy::default();
+
+    assert!(policy.is_retryable(&server_error(StatusCode::BAD_GATEWAY)));
+    assert!(policy.is_retryable(&ApiError::RateLimited { retry_after: None }));
+    assert!(!policy.is_retryable(&ApiError::NotFound));
+    assert!(!policy.is_retryable(&server_error(StatusCode::UNPROCESSABLE_ENTITY)));
+    assert!(!policy
+        .statuses([StatusCode::SERVICE_UNAVAILABLE])
+        .is_retryable(&server_error(StatusCode::BAD_GATEWAY)));
+}
+
+#[test]
+fn it_only_retries_idempotent_requests() {
+    let policy = RetryPolicy::default().max_attempts(3);
+
+    assert!(policy.allows_retry(&Method::GET, 2));
+    assert!(!policy.allows_retry(&Method::GET, 3));
+    assert!(!policy.allows_retry(&Method::POST, 1));
+    assert!(!RetryPolicy::none().allows_retry(&Method::GET, 1));
+}
@@ -0,0 +1,117 @@
+use crate::catalogue::normalize::{normalize, romaji_variant};
+use crate::catalogue::Catalogue;
+use crate::models::Anime;
+
+fn catalogue() -> Catalogue {
+    let anime: Vec<Anime> = serde_json::from_str(
+        r#"[
+            {
+                "id": 1,
+                "name": "Bakemonogatari",
+                "slug": "bakemonogatari",
+                "animesynonyms": [{"id": 100, "text": "化物語"}],
+                "animethemes": [
+                    {
+                        "id": 10,
+                        "slug": "OP1",
+                        "song": {"id": 20, "title": "Staple Stable"}
+                    }
+                ]
+            },
+            {"id": 2, "name": "Nisemonogatari", "slug": "nisemonogatari"},
+            {
+                "id": 3,
+                "name": "Tokyo Ghoul",
+                "slug": "tokyo_ghoul",
+                "animesynonyms": [{"id": 101, "text": "東京喰種トーキョーグール"}]
+            },
+            {"id": 4, "name": "Sakamoto desu ga?", "slug": "sakamoto_desu_ga"}
+        ]"#,
+    )
+    .unwrap();
+    let mut catalogue = Catalogue::new();
+    anime.into_iter().for_each(|a| catalogue.insert_anime(a));
+
+    catalogue
+}
+
+fn anime_names(catalogue: &Catalogue, query: &str) -> Vec<String> {
+    catalogue
+        .search(query, 10)
+        .anime
+        .unwrap()
+        .into_iter()
+        .filter_map(|a| a.name)
+        .collect()
+}
+
+#[test]
+fn it_normalizes_kana_and_diacritics() {
+    let variant = |text: &str| romaji_variant(&normalize(text));
+
+    assert_eq!(variant("トーキョー"), "tokyo");
+    assert_eq!(variant("トウキョウ"), "tokyo");
+    assert_eq!(normalize("Tōkyō"), "tokyo");
+    assert_eq!(normalize("Toukyou"), "toukyou");
+    assert_eq!(variant("Toukyou"), "tokyo");
+    assert_eq!(variant("Book of Souls"), "book of souls");
+    assert_eq!(normalize("しゃっきん"), "shakkin");
+    assert_eq!(normalize("ＢＡＫＥ-monogatari!"), "bake monogatari");
+}
+
+#[test]
+fn it_ranks_exact_matches_first() {
+    let catalogue = catalogue();
+
+    assert_eq!(
+        anime_names(&catalogue, "bakemonogatari"),
+        vec!["Bakemonogatari"]
+    );
+    assert_eq!(anime_names(&catalogue, "toukyou")[0], "Tokyo Ghoul");
+    assert!(anime_names(&catalogue, "naruto").is_empty());
+}
+
+#[test]
+fn it_tolerates_typos() {
+    let catalogue = catalogue();
+
+    assert_eq!(
+        anime_names(&catalogue, "bakemonogtari")[0],
+        "Bakemonogatari"
+    );
+    assert_eq!(anime_names(&catalogue, "skamoto")[0], "Sakamoto desu ga?");
+}
+
+#[test]
+fn it_searches_synonyms_and_songs() {
+    let catalogue = catalogue();
+
+    assert_eq!(
+        anime_names(&catalogue, "トーキョーグール")[0],
+        "Tokyo Ghoul"
+    );
+
+    let response = catalogue.search("staple stable", 10);
+    assert_eq!(
+        response.songs.unwrap()[0].title.as_deref(),
+        Some("Staple Stable")
+    );
+    let themes = response.themes.unwrap();
+    assert_eq!(themes[0].slug.as_deref(), Some("OP1"));
+    assert_eq!(
+        themes[0].anime.as_ref().unwrap().name.as_deref(),
+        Some("Bakemonogatari")
+    );
+}
+
+#[test]
+fn it_keeps_long_vowels_of_english_words() {
+    let mut catalogue = catalogue();
+    catalogue.insert_anime(
+        serde_json::from_str(r#"{"id": 5, "name": "Book Girl", "slug": "book_girl"}"#).unwrap(),
+    );
+
+    assert!(anime_names(&catalogue, "bok").is_empty());
+    assert_eq!(anime_names(&catalogue, "book girl"), vec!["Book Girl"]);
+    assert_eq!(anime_names(&catalogue, "toukyou guuru")[0], "Tokyo Ghoul");
+}
@@ -0,0 +1,41 @@
+use crate::sort::*;
+
+#[test]
+fn it_serializes_multi_key_sorts() {
+    let sort = Sort::desc(AnimeSort::Year).then_asc(AnimeSort::Name);
+
+    assert_eq!(
+        sort.query(false),
+        vec![("sort".to_string(), "-year,name".to_string())]
+    );
+}
+
+#[test]
+fn it_serializes_random_sorts() {
+    let sort: Sort<VideoSort> = Sort::random();
+
+    assert_eq!(
+        sort.query(true),
+        vec![("sort[video]".to_string(), "random".to_string())]
+    );
+}
+
+#[test]
+fn it_replaces_random_sorts_with_keys() {
+    let sort = Sort::random().then_desc(VideoSort::Resolution);
+
+    assert_eq!(sort.value(), "-resolution");
+}
+
+#[test]
+fn it_omits_empty_sorts() {
+    let sorts = SearchSorts {
+        videos: Sort::desc(VideoSort::Resolution),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        sorts.query(),
+        vec![("sort[video]".to_string(), "-resolution".to_string())]
+    );
+}
@@ -0,0 +1,67 @@
+//! The http layer of the client.
+//!
+//! Requests built by the [AnimeThemesClient](crate::client::AnimeThemesClient) are sent
+//! through a [Transport]. By default that's the reqwest client, but any implementation
+//! can be configured with
+//! [AnimeThemesClientBuilder::transport](crate::client::AnimeThemesClientBuilder::transport),
+//! e.g. to serve canned responses in tests.
+//!
+//! ```
+//! use animethemes_rs::client::AnimeThemesClient;
+//! use animethemes_rs::error::ApiResult;
+//! use animethemes_rs::transport::{response, Transport};
+//! use futures::future::BoxFuture;
+//! use reqwest::header::HeaderMap;
+//! use reqwest::{Request, Response, StatusCode};
+//!
+//! #[derive(Debug)]
+//! struct NotFound;
+//!
+//! impl Transport for NotFound {
+//!     fn execute(&self, _: Request) -> BoxFuture<'_, ApiResult<Response>> {
+//!         Box::pin(async { Ok(response(StatusCode::NOT_FOUND, HeaderMap::new(), "")) })
+//!     }
+//! }
+//!
+//! # fn a() -> ApiResult<()> {
+//! let client = AnimeThemesClient::builder().transport(NotFound).build()?;
+//! # Ok(()) }
+//! ```
+
+mod record;
+
+pub use record::{Recorder, Replayer};
+
+use crate::error::ApiResult;
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::{Body, Request, Response, StatusCode};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Sends requests and returns the responses
+pub trait Transport: Debug + Send + Sync {
+    /// Sends the request and returns the response regardless of its status
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>>;
+}
+
+impl Transport for reqwest::Client {
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>> {
+        Box::pin(async move { Ok(reqwest::Client::execute(self, request).await?) })
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>> {
+        (**self).execute(request)
+    }
+}
+
+/// Builds a response for transports that don't receive them over the network
+pub fn response<B: Into<Body>>(status: StatusCode, headers: HeaderMap, body: B) -> Response {
+    let mut response = http::Response::new(body.into());
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+
+    Response::from(response)
+}
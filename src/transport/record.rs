@@ -0,0 +1,221 @@
+use crate::cache::stable_hash_bytes;
+use crate::error::{ApiError, ApiResult};
+use crate::transport::{response, Transport};
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A recorded response stored as a json file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Fixture {
+    /// the method and normalized url of the request with a hash of its body
+    request: String,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: FixtureBody,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FixtureBody {
+    Json(Value),
+    Text(String),
+}
+
+impl Fixture {
+    fn file_name(request: &str) -> String {
+        format!("{:016x}.json", stable_hash_bytes(request.as_bytes()))
+    }
+
+    fn into_response(self, status: StatusCode) -> Response {
+        let headers = self
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect::<HeaderMap>();
+        let body = match self.body {
+            FixtureBody::Json(value) => value.to_string(),
+            FixtureBody::Text(text) => text,
+        };
+
+        response(status, headers, body)
+    }
+}
+
+/// Returns the method and url of a request in a form that doesn't depend
+/// on the order of query parameters or includes. The host is ignored so fixtures
+/// recorded against one endpoint can be replayed against another.
+/// Requests with a body are told apart by a hash of the body.
+fn normalize(request: &Request) -> String {
+    let url = request.url();
+    let mut query: Vec<String> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            if key == "include" || key.starts_with("include[") {
+                let mut includes: Vec<&str> = value.split(',').collect();
+                includes.sort_unstable();
+
+                format!("{key}={}", includes.join(","))
+            } else {
+                format!("{key}={value}")
+            }
+        })
+        .collect();
+    query.sort();
+
+    let key = format!("{} {}?{}", request.method(), url.path(), query.join("&"));
+
+    match request.body().and_then(|body| body.as_bytes()) {
+        Some(body) if !body.is_empty() => {
+            format!("{key}#{:016x}", stable_hash_bytes(body))
+        }
+        _ => key,
+    }
+}
+
+/// A transport that sends requests through another transport and writes every
+/// response to a directory of json fixtures that can be served by a [Replayer].
+///
+/// Responses are read completely before they're returned, so downloads aren't streamed
+/// while recording.
+///
+/// ```no_run
+/// # use animethemes_rs::error::ApiResult;
+/// use animethemes_rs::client::AnimeThemesClient;
+/// use animethemes_rs::transport::Recorder;
+///
+/// # fn a() -> ApiResult<()> {
+/// let client = AnimeThemesClient::builder()
+///     .transport(Recorder::new(reqwest::Client::new(), "tests/fixtures"))
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct Recorder<T: Transport> {
+    inner: T,
+    directory: PathBuf,
+}
+
+impl<T: Transport> Recorder<T> {
+    pub fn new<P: Into<PathBuf>>(inner: T, directory: P) -> Self {
+        Self {
+            inner,
+            directory: directory.into(),
+        }
+    }
+
+    async fn record(&self, request: Request) -> ApiResult<Response> {
+        let key = normalize(&request);
+        let recorded = self.inner.execute(request).await?;
+        let status = recorded.status();
+        let headers = recorded.headers().clone();
+        let bytes = recorded.bytes().await?;
+
+        let body = match serde_json::from_slice(&bytes) {
+            Ok(value) => FixtureBody::Json(value),
+            Err(_) => FixtureBody::Text(String::from_utf8_lossy(&bytes).into_owned()),
+        };
+        let fixture = Fixture {
+            request: key,
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body,
+        };
+        tokio::fs::create_dir_all(&self.directory).await?;
+        tokio::fs::write(
+            self.directory.join(Fixture::file_name(&fixture.request)),
+            serde_json::to_vec_pretty(&fixture)?,
+        )
+        .await?;
+        tracing::debug!("recorded fixture for {}", fixture.request);
+
+        Ok(response(status, headers, bytes))
+    }
+}
+
+impl<T: Transport> Transport for Recorder<T> {
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>> {
+        Box::pin(self.record(request))
+    }
+}
+
+/// A transport serving the fixtures written by a [Recorder].
+/// Requests are matched by their method, path, query and body. The order of query
+/// parameters and includes doesn't matter.
+/// Requests without a fixture fail with [ApiError::MissingFixture].
+///
+/// ```no_run
+/// # use animethemes_rs::error::ApiResult;
+/// use animethemes_rs::client::AnimeThemesClient;
+/// use animethemes_rs::transport::Replayer;
+///
+/// # fn a() -> ApiResult<()> {
+/// let client = AnimeThemesClient::builder()
+///     .transport(Replayer::load("tests/fixtures")?)
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Default)]
+pub struct Replayer {
+    fixtures: HashMap<String, (StatusCode, Fixture)>,
+}
+
+impl Replayer {
+    /// Loads all fixtures of the directory
+    pub fn load<P: AsRef<Path>>(directory: P) -> ApiResult<Self> {
+        let mut fixtures = HashMap::new();
+
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path.extension().is_some_and(|e| e == "json") {
+                let fixture: Fixture = serde_json::from_slice(&std::fs::read(&path)?)?;
+
+                match StatusCode::from_u16(fixture.status) {
+                    Ok(status) => {
+                        fixtures.insert(fixture.request.clone(), (status, fixture));
+                    }
+                    Err(e) => tracing::warn!("invalid fixture {}: {e}", path.display()),
+                }
+            }
+        }
+
+        Ok(Self { fixtures })
+    }
+
+    /// Returns the number of loaded fixtures
+    pub fn len(&self) -> usize {
+        self.fixtures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fixtures.is_empty()
+    }
+}
+
+impl Transport for Replayer {
+    fn execute(&self, request: Request) -> BoxFuture<'_, ApiResult<Response>> {
+        let key = normalize(&request);
+
+        let result = match self.fixtures.get(&key) {
+            Some((status, fixture)) => Ok(fixture.clone().into_response(*status)),
+            None => Err(ApiError::MissingFixture(key)),
+        };
+
+        Box::pin(async move { result })
+    }
+}